@@ -0,0 +1,35 @@
+use crate::{EntityHandle, EntityPool, SlotKey};
+
+impl EntityPool {
+    /// Marks the handle's slot read-only for later pipeline stages, until it is released.
+    ///
+    /// Tasks queued with [`PooledTasks::spawn_staged`](crate::PooledTasks::spawn_staged) still see
+    /// published slots after copy-in but never copy them back. In debug builds a stage that changes
+    /// a published slot's components panics once it finished, naming the slot.
+    ///
+    /// # Panics
+    /// Panics if `handle` doesn't belong to this pool.
+    pub fn publish(&mut self, handle: &EntityHandle) {
+        assert!(
+            self.is_valid(handle.key()),
+            "invalid handle {:?} - not checked out from this pool",
+            handle.key()
+        );
+        self.published[handle.key().index() as usize] = true;
+    }
+
+    /// Makes a published slot writable again. Returns `false` if `key` is stale.
+    pub fn unpublish(&mut self, key: SlotKey) -> bool {
+        if !self.is_valid(key) {
+            return false;
+        }
+
+        self.published[key.index() as usize] = false;
+        true
+    }
+
+    /// Returns `true` if `key` refers to a live, published slot.
+    pub fn is_published(&self, key: SlotKey) -> bool {
+        self.is_valid(key) && self.published[key.index() as usize]
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{EntityPool, PoolOp, SlotKey};
+use std::{collections::VecDeque, fmt::Write};
+
+/// Number of pool generations whose [`GenerationStats`] are kept.
+pub const GENERATION_HISTORY: usize = 16;
+
+/// Age bucket of a pool, advanced explicitly with [`EntityPool::next_generation`] at job
+/// boundaries.
+///
+/// Unrelated to the per-slot generation in [`SlotKey`], which only tells successive checkouts of
+/// one slot apart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PoolGeneration(u64);
+
+impl PoolGeneration {
+    /// The generation's number - 0 for a new pool, counting up with every
+    /// [`EntityPool::next_generation`].
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+/// Usage of a pool during one [`PoolGeneration`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GenerationStats {
+    /// Generation the statistics belong to.
+    pub generation: PoolGeneration,
+    /// Slots checked out during the generation.
+    pub acquisitions: u64,
+    /// Slots checked out during the generation that were released since, in any generation.
+    pub releases: u64,
+    /// Acquisitions that failed during the generation because the pool was exhausted.
+    pub failed_acquisitions: u64,
+    /// Highest number of slots in use at once during the generation, including slots still checked
+    /// out from earlier generations.
+    pub peak_in_use: usize,
+}
+
+impl GenerationStats {
+    /// Slots from this generation that are still checked out.
+    pub fn live(&self) -> u64 {
+        // releases are only recorded for checked out slots, saturating is just a safety net
+        self.acquisitions.saturating_sub(self.releases)
+    }
+}
+
+pub(crate) struct GenerationTracker {
+    current: u64,
+    /// Generation every checked out slot was acquired in, `None` for free slots.
+    acquired_in: Vec<Option<u64>>,
+    /// Oldest first, the current generation is last.
+    history: VecDeque<GenerationStats>,
+    max_age: Option<u64>,
+}
+
+impl GenerationTracker {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            current: 0,
+            acquired_in: vec![None; capacity],
+            history: VecDeque::from([GenerationStats::default()]),
+            max_age: None,
+        }
+    }
+
+    /// Starts over at generation 0, keeping the maximum age.
+    pub(crate) fn reset(&mut self, capacity: usize) {
+        *self = Self {
+            max_age: self.max_age,
+            ..Self::with_capacity(capacity)
+        };
+    }
+
+    pub(crate) fn resize(&mut self, capacity: usize) {
+        self.acquired_in.resize(capacity, None);
+    }
+
+    pub(crate) fn record_acquire(&mut self, slot: u32, in_use: usize) {
+        self.acquired_in[slot as usize] = Some(self.current);
+        let stats = self.current_mut();
+        stats.acquisitions += 1;
+        stats.peak_in_use = stats.peak_in_use.max(in_use);
+    }
+
+    pub(crate) fn record_release(&mut self, slot: u32) {
+        // free slots are released too, e.g. when recovering lost entities
+        let Some(generation) = self.acquired_in[slot as usize].take() else {
+            return;
+        };
+        // slots can outlive their generation's stats
+        if let Some(stats) = self.stats_mut(generation) {
+            stats.releases += 1;
+        }
+    }
+
+    pub(crate) fn record_failure(&mut self) {
+        self.current_mut().failed_acquisitions += 1;
+    }
+
+    fn current_mut(&mut self) -> &mut GenerationStats {
+        self.history
+            .back_mut()
+            .expect("the current generation is always tracked")
+    }
+
+    fn stats_mut(&mut self, generation: u64) -> Option<&mut GenerationStats> {
+        let oldest = self.history.front()?.generation.0;
+        let index = generation.checked_sub(oldest)?;
+        self.history.get_mut(index as usize)
+    }
+}
+
+impl EntityPool {
+    /// The current generation - slots checked out now are recorded in it, see
+    /// [`EntityPool::acquired_in`].
+    pub fn generation(&self) -> PoolGeneration {
+        PoolGeneration(self.ages.current)
+    }
+
+    /// Starts a new pool generation, e.g. between independent jobs, and returns it.
+    ///
+    /// # Panics
+    /// Panics if a maximum age is set (see [`EntityPool::set_max_generation_age`]) and slots from
+    /// an older generation are still checked out. The message lists them, with their call sites
+    /// if leak detection is enabled.
+    #[track_caller]
+    pub fn next_generation(&mut self) -> PoolGeneration {
+        self.ages.current += 1;
+        if self.ages.history.len() == GENERATION_HISTORY {
+            self.ages.history.pop_front();
+        }
+        let generation = PoolGeneration(self.ages.current);
+        self.ages.history.push_back(GenerationStats {
+            generation,
+            peak_in_use: self.in_use_count(),
+            ..GenerationStats::default()
+        });
+        self.log(PoolOp::NextGeneration {
+            generation: generation.0,
+        });
+
+        if let Some(max_age) = self.ages.max_age {
+            let stale: Vec<SlotKey> = self.slots_older_than(max_age).collect();
+            if !stale.is_empty() {
+                panic!(
+                    "{} slots outlived the maximum age of {max_age} generations into {generation:?}:{}",
+                    stale.len(),
+                    self.describe_stale(&stale)
+                );
+            }
+        }
+        generation
+    }
+
+    /// Makes [`EntityPool::next_generation`] panic if slots checked out in generation `N` are still
+    /// in use in generation `N + max_age + 1` - `Some(1)` asserts nothing from `N` survives into
+    /// `N + 2`. `None` disables the check.
+    pub fn set_max_generation_age(&mut self, max_age: Option<u64>) {
+        self.ages.max_age = max_age;
+    }
+
+    pub fn max_generation_age(&self) -> Option<u64> {
+        self.ages.max_age
+    }
+
+    /// Generation the slot behind `key` was checked out in, `None` if the key is stale.
+    pub fn acquired_in(&self, key: SlotKey) -> Option<PoolGeneration> {
+        if !self.is_valid(key) {
+            return None;
+        }
+        self.ages.acquired_in[key.index() as usize].map(PoolGeneration)
+    }
+
+    /// In use slots checked out more than `age` generations ago.
+    pub fn slots_older_than(&self, age: u64) -> impl Iterator<Item = SlotKey> + '_ {
+        let current = self.ages.current;
+        (0..self.entities.len() as u32)
+            .filter(move |&slot| {
+                self.ages.acquired_in[slot as usize]
+                    .is_some_and(|acquired_in| current - acquired_in > age)
+            })
+            .map(|slot| SlotKey::new(slot, self.generations[slot as usize]))
+    }
+
+    /// Statistics of `generation`, `None` if it's in the future or older than the last
+    /// [`GENERATION_HISTORY`] generations.
+    pub fn generation_stats(&self, generation: PoolGeneration) -> Option<GenerationStats> {
+        let oldest = self.ages.history.front()?.generation.0;
+        let index = generation.0.checked_sub(oldest)?;
+        self.ages.history.get(index as usize).copied()
+    }
+
+    /// Statistics of the last [`GENERATION_HISTORY`] generations, oldest first.
+    pub fn generation_history(&self) -> impl Iterator<Item = &GenerationStats> + '_ {
+        self.ages.history.iter()
+    }
+
+    fn describe_stale(&self, stale: &[SlotKey]) -> String {
+        let mut description = String::new();
+        for &key in stale {
+            let slot = key.index() as usize;
+            let _ = write!(
+                description,
+                "\n{key:?} {:?} from {:?}",
+                self.entities[slot],
+                PoolGeneration(self.ages.acquired_in[slot].unwrap_or_default())
+            );
+            if let Some(location) = self.acquired_at.as_ref().and_then(|sites| sites[slot]) {
+                let _ = write!(description, " acquired at {location}");
+            }
+        }
+        description
+    }
+}
@@ -0,0 +1,342 @@
+use crate::{EntityHandle, EntityPool};
+use std::{collections::VecDeque, ops::Range};
+
+/// Decides which free slot an [`EntityPool`] hands out next.
+///
+/// The pool tracks which slots are in use - an allocator only keeps its own view of the free
+/// slots in sync through the calls below, and is never asked for a slot it didn't report free.
+pub trait SlotAllocator: Send + Sync + 'static {
+    /// Adds `slots` as free. Called on pool creation and when the pool grows.
+    fn grow(&mut self, slots: Range<u32>);
+
+    /// Picks a free slot and removes it from the free set.
+    fn allocate(&mut self) -> Option<u32>;
+
+    /// Like [`SlotAllocator::allocate`], with a caller provided placement hint such as a chunk id.
+    /// Ignores the hint by default.
+    fn allocate_hinted(&mut self, hint: u64) -> Option<u32> {
+        let _ = hint;
+        self.allocate()
+    }
+
+    /// Removes a specific slot from the free set - the pool checks it out directly, e.g. for
+    /// blocks. Returns `false` if the slot wasn't free.
+    fn take(&mut self, slot: u32) -> bool;
+
+    /// Removes every slot of `slots` from the free set, e.g. for a block. Returns `false` and
+    /// takes nothing if any of them wasn't free. Takes one slot at a time by default, freeing the
+    /// taken ones again on failure.
+    fn take_range(&mut self, slots: Range<u32>) -> bool {
+        for slot in slots.clone() {
+            if !self.take(slot) {
+                for taken in slots.start..slot {
+                    self.free(taken);
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns a slot to the free set.
+    fn free(&mut self, slot: u32);
+
+    /// Number of free slots.
+    fn available(&self) -> usize;
+
+    /// Forgets every slot, as if no slot was ever added. Called by
+    /// [`EntityPool::reset`](crate::EntityPool::reset) before adding all slots again.
+    fn clear(&mut self);
+}
+
+/// Hands out the most recently freed slot first, keeping the working set small. The default.
+#[derive(Clone, Debug, Default)]
+pub struct StackAllocator {
+    free: Vec<u32>,
+}
+
+impl SlotAllocator for StackAllocator {
+    fn grow(&mut self, slots: Range<u32>) {
+        // reversed so slots are handed out in reservation order
+        self.free.extend(slots.rev());
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        self.free.pop()
+    }
+
+    fn take(&mut self, slot: u32) -> bool {
+        let len = self.free.len();
+        self.free.retain(|&free| free != slot);
+        self.free.len() != len
+    }
+
+    fn take_range(&mut self, slots: Range<u32>) -> bool {
+        let free = self.free.iter().filter(|free| slots.contains(free)).count();
+        if free != slots.len() {
+            return false;
+        }
+        self.free.retain(|free| !slots.contains(free));
+        true
+    }
+
+    fn free(&mut self, slot: u32) {
+        self.free.push(slot);
+    }
+
+    fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    fn clear(&mut self) {
+        self.free.clear();
+    }
+}
+
+/// Hands out the least recently freed slot first - freed slots rest as long as possible before
+/// they're reused, which surfaces stale entity references sooner.
+#[derive(Clone, Debug, Default)]
+pub struct RingAllocator {
+    free: VecDeque<u32>,
+}
+
+impl SlotAllocator for RingAllocator {
+    fn grow(&mut self, slots: Range<u32>) {
+        self.free.extend(slots);
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        self.free.pop_front()
+    }
+
+    fn take(&mut self, slot: u32) -> bool {
+        let len = self.free.len();
+        self.free.retain(|&free| free != slot);
+        self.free.len() != len
+    }
+
+    fn take_range(&mut self, slots: Range<u32>) -> bool {
+        let free = self.free.iter().filter(|free| slots.contains(free)).count();
+        if free != slots.len() {
+            return false;
+        }
+        self.free.retain(|free| !slots.contains(free));
+        true
+    }
+
+    fn free(&mut self, slot: u32) {
+        self.free.push_back(slot);
+    }
+
+    fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    fn clear(&mut self) {
+        self.free.clear();
+    }
+}
+
+/// Hands out the lowest free slot, keeping used slots packed at the start of the pool.
+#[derive(Clone, Debug, Default)]
+pub struct BitmapAllocator {
+    /// Set bits are free slots.
+    words: Vec<u64>,
+    available: usize,
+}
+
+impl BitmapAllocator {
+    fn set(&mut self, slot: u32, free: bool) -> bool {
+        let (word, bit) = ((slot / 64) as usize, 1 << (slot % 64));
+        let Some(word) = self.words.get_mut(word) else {
+            return false;
+        };
+        let was_free = *word & bit != 0;
+        if free {
+            *word |= bit;
+        } else {
+            *word &= !bit;
+        }
+        was_free
+    }
+}
+
+impl SlotAllocator for BitmapAllocator {
+    fn grow(&mut self, slots: Range<u32>) {
+        self.words.resize(slots.end.div_ceil(64) as usize, 0);
+        for slot in slots {
+            if !self.set(slot, true) {
+                self.available += 1;
+            }
+        }
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        let (index, word) = self.words.iter().enumerate().find(|(_, &word)| word != 0)?;
+        let slot = index as u32 * 64 + word.trailing_zeros();
+        self.take(slot).then_some(slot)
+    }
+
+    fn take(&mut self, slot: u32) -> bool {
+        let was_free = self.set(slot, false);
+        if was_free {
+            self.available -= 1;
+        }
+        was_free
+    }
+
+    fn free(&mut self, slot: u32) {
+        if !self.set(slot, true) {
+            self.available += 1;
+        }
+    }
+
+    fn available(&self) -> usize {
+        self.available
+    }
+
+    fn clear(&mut self) {
+        self.words.clear();
+        self.available = 0;
+    }
+}
+
+/// Partitions slots into fixed size groups of consecutive slots and places hinted allocations in
+/// the hint's group - e.g. hint with a chunk id so a chunk's entities stay close together.
+/// Unhinted allocations and full groups fall back to the first group with a free slot.
+#[derive(Clone, Debug)]
+pub struct GroupedAllocator {
+    group_size: u32,
+    /// Free slots per group, lowest last.
+    groups: Vec<Vec<u32>>,
+    available: usize,
+}
+
+impl GroupedAllocator {
+    /// # Panics
+    /// Panics if `group_size` is 0.
+    pub fn new(group_size: u32) -> Self {
+        assert!(group_size > 0, "groups need at least one slot");
+        Self {
+            group_size,
+            groups: Vec::new(),
+            available: 0,
+        }
+    }
+
+    pub fn group_size(&self) -> u32 {
+        self.group_size
+    }
+
+    /// Number of groups the pool's slots are partitioned into.
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Group `slot` belongs to.
+    pub fn group_of(&self, slot: u32) -> usize {
+        (slot / self.group_size) as usize
+    }
+
+    fn allocate_from(&mut self, group: usize) -> Option<u32> {
+        let slot = self.groups.get_mut(group)?.pop()?;
+        self.available -= 1;
+        Some(slot)
+    }
+}
+
+impl SlotAllocator for GroupedAllocator {
+    fn grow(&mut self, slots: Range<u32>) {
+        self.groups
+            .resize(slots.end.div_ceil(self.group_size) as usize, Vec::new());
+        for slot in slots {
+            let group = self.group_of(slot);
+            self.groups[group].push(slot);
+            self.available += 1;
+        }
+        // grown slots can land in a group with freed slots out of order
+        for group in &mut self.groups {
+            group.sort_unstable_by(|a, b| b.cmp(a));
+        }
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        let group = self.groups.iter().position(|group| !group.is_empty())?;
+        self.allocate_from(group)
+    }
+
+    fn allocate_hinted(&mut self, hint: u64) -> Option<u32> {
+        if self.groups.is_empty() {
+            return None;
+        }
+        let group = (hint % self.groups.len() as u64) as usize;
+        self.allocate_from(group).or_else(|| self.allocate())
+    }
+
+    fn take(&mut self, slot: u32) -> bool {
+        let group = self.group_of(slot);
+        let Some(free) = self.groups.get_mut(group) else {
+            return false;
+        };
+        let len = free.len();
+        free.retain(|&free| free != slot);
+        let taken = free.len() != len;
+        if taken {
+            self.available -= 1;
+        }
+        taken
+    }
+
+    fn take_range(&mut self, slots: Range<u32>) -> bool {
+        if slots.is_empty() {
+            return true;
+        }
+        let (first, last) = (self.group_of(slots.start), self.group_of(slots.end - 1));
+        let groups = first..last + 1;
+        let Some(range_groups) = self.groups.get(groups.clone()) else {
+            return false;
+        };
+        let free: usize = range_groups
+            .iter()
+            .map(|free| free.iter().filter(|free| slots.contains(free)).count())
+            .sum();
+        if free != slots.len() {
+            return false;
+        }
+        for free in &mut self.groups[groups] {
+            free.retain(|free| !slots.contains(free));
+        }
+        self.available -= free;
+        true
+    }
+
+    fn free(&mut self, slot: u32) {
+        let group = self.group_of(slot);
+        let group = &mut self.groups[group];
+        let index = group.partition_point(|&free| free > slot);
+        group.insert(index, slot);
+        self.available += 1;
+    }
+
+    fn available(&self) -> usize {
+        self.available
+    }
+
+    fn clear(&mut self) {
+        self.groups.clear();
+        self.available = 0;
+    }
+}
+
+impl EntityPool {
+    /// Like [`EntityPool::try_get`], passing `hint` to the allocator - see
+    /// [`SlotAllocator::allocate_hinted`].
+    #[track_caller]
+    pub fn try_get_hinted(&mut self, hint: u64) -> Option<EntityHandle> {
+        let Some(slot) = self.allocator.allocate_hinted(hint) else {
+            self.record_failure();
+            return None;
+        };
+        Some(self.checkout(slot))
+    }
+}
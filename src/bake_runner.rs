@@ -0,0 +1,436 @@
+//! Scaffolding for standalone content-baking binaries built on pooled tasks.
+//!
+//! [`BakeRunner`] builds a headless app with an [`EntityPool`] and [`PooledTaskPlugin`], runs a list
+//! of [`BakeJob`]s to completion and returns a [`BakeReport`] with timings and pool occupancy that
+//! serializes to JSON for CI dashboards.
+//!
+//! ```ignore
+//! let report = BakeRunner::new(4096)
+//!     .seed(7)
+//!     .configure(|app| {
+//!         app.register_type::<Tile>();
+//!     })
+//!     .job(BakeJob::new("terrain", 1024, bake_terrain).on_finished(save_tiles))
+//!     .run();
+//! std::fs::write("bake_report.json", report.to_json())?;
+//! ```
+
+use crate::{
+    EntityPool, PooledTaskFinished, PooledTaskId, PooledTaskPlugin, PooledTaskRejected,
+    PooledTasks, ScratchContext,
+};
+use bevy::{
+    app::App,
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    ecs::{
+        entity::Entity,
+        event::{Events, ManualEventReader},
+        reflect::AppTypeRegistry,
+        world::{Mut, World},
+    },
+    utils::{Duration, HashMap, Instant},
+};
+use std::fmt::Write;
+
+type BakeTask = Box<dyn FnOnce(&mut ScratchContext) + Send>;
+type FinishedCallback = Box<dyn FnOnce(&mut World, &[Entity])>;
+
+/// A named pooled task run by [`BakeRunner`].
+pub struct BakeJob {
+    name: String,
+    slots: usize,
+    task: BakeTask,
+    on_finished: Option<FinishedCallback>,
+}
+
+impl BakeJob {
+    /// Runs `task` in a scratch world with `slots` pooled entities.
+    pub fn new(
+        name: impl Into<String>,
+        slots: usize,
+        task: impl FnOnce(&mut ScratchContext) + Send + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            slots,
+            task: Box::new(task),
+            on_finished: None,
+        }
+    }
+
+    /// Called with the main world and the job's entities once its results were merged, before the
+    /// entities return to the pool - write the baked content out here.
+    pub fn on_finished(mut self, callback: impl FnOnce(&mut World, &[Entity]) + 'static) -> Self {
+        self.on_finished = Some(Box::new(callback));
+        self
+    }
+}
+
+/// How a [`BakeJob`] ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BakeOutcome {
+    Finished,
+    /// Results failed validation, holds the formatted [`ValidationReport`](crate::ValidationReport).
+    Rejected(String),
+    /// Cancelled, e.g. by the [`PooledTaskWatchdogPlugin`](crate::PooledTaskWatchdogPlugin).
+    Cancelled,
+    /// Still queued or running when the runner's timeout elapsed.
+    TimedOut,
+    /// Needs more slots than the pool has.
+    TooLarge,
+}
+
+impl BakeOutcome {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Finished => "finished",
+            Self::Rejected(_) => "rejected",
+            Self::Cancelled => "cancelled",
+            Self::TimedOut => "timed_out",
+            Self::TooLarge => "too_large",
+        }
+    }
+}
+
+/// Timing of a single job.
+#[derive(Clone, Debug)]
+pub struct BakeJobReport {
+    pub name: String,
+    pub slots: usize,
+    /// Seed of the job's [`ScratchRng`](crate::ScratchRng), `None` if it never started.
+    pub seed: Option<u64>,
+    pub outcome: BakeOutcome,
+    /// Frame the job was spawned on.
+    pub started_frame: Option<u64>,
+    /// Time from spawning to merging, measured at frame granularity.
+    pub duration: Duration,
+}
+
+/// Result of [`BakeRunner::run`].
+#[derive(Clone, Debug)]
+pub struct BakeReport {
+    pub capacity: usize,
+    pub frames: u64,
+    pub duration: Duration,
+    /// Highest number of slots in use at the end of a frame.
+    pub peak_in_use: usize,
+    /// Average fraction of slots in use at the end of a frame.
+    pub mean_occupancy: f32,
+    pub jobs: Vec<BakeJobReport>,
+}
+
+impl BakeReport {
+    /// Returns `true` if every job finished.
+    pub fn is_success(&self) -> bool {
+        self.jobs
+            .iter()
+            .all(|job| job.outcome == BakeOutcome::Finished)
+    }
+
+    /// Serializes the report as a JSON object.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        let _ = write!(
+            json,
+            "{{\"capacity\":{},\"frames\":{},\"duration_secs\":{},\"peak_in_use\":{},\
+             \"mean_occupancy\":{},\"success\":{},\"jobs\":[",
+            self.capacity,
+            self.frames,
+            self.duration.as_secs_f64(),
+            self.peak_in_use,
+            self.mean_occupancy,
+            self.is_success()
+        );
+        for (i, job) in self.jobs.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"name\":");
+            push_json_string(&mut json, &job.name);
+            let _ = write!(
+                json,
+                ",\"slots\":{},\"outcome\":\"{}\"",
+                job.slots,
+                job.outcome.name()
+            );
+            if let BakeOutcome::Rejected(report) = &job.outcome {
+                json.push_str(",\"report\":");
+                push_json_string(&mut json, report);
+            }
+            // seeds don't fit in a double, keep them exact as hex strings
+            match job.seed {
+                Some(seed) => _ = write!(json, ",\"seed\":\"{seed:#018x}\""),
+                None => json.push_str(",\"seed\":null"),
+            }
+            match job.started_frame {
+                Some(frame) => _ = write!(json, ",\"started_frame\":{frame}"),
+                None => json.push_str(",\"started_frame\":null"),
+            }
+            let _ = write!(json, ",\"duration_secs\":{}}}", job.duration.as_secs_f64());
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => _ = write!(json, "\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+type ConfigureApp = Box<dyn FnOnce(&mut App)>;
+
+/// Headless app running [`BakeJob`]s to completion, see the [module docs](self).
+///
+/// Jobs start in order as soon as the pool has room for them, so a pool smaller than the sum of all
+/// jobs still bakes everything - just with less parallelism.
+pub struct BakeRunner {
+    capacity: usize,
+    seed: u64,
+    timeout: Option<Duration>,
+    frame_interval: Duration,
+    configure: Vec<ConfigureApp>,
+    jobs: Vec<BakeJob>,
+}
+
+impl BakeRunner {
+    /// Runner with a pool of `capacity` entities.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            seed: 0,
+            timeout: None,
+            frame_interval: Duration::ZERO,
+            configure: Vec::new(),
+            jobs: Vec::new(),
+        }
+    }
+
+    /// User seed job seeds are derived from, see [`PooledTasks::set_seed`].
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Gives up on jobs that didn't finish within `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sleeps between frames instead of just yielding, leaving more CPU to the task pool.
+    pub fn frame_interval(mut self, interval: Duration) -> Self {
+        self.frame_interval = interval;
+        self
+    }
+
+    /// Customizes the app before it runs - register reflected types, add validators or plugins.
+    pub fn configure(mut self, configure: impl FnOnce(&mut App) + 'static) -> Self {
+        self.configure.push(Box::new(configure));
+        self
+    }
+
+    pub fn job(mut self, job: BakeJob) -> Self {
+        self.jobs.push(job);
+        self
+    }
+
+    /// Builds the app and updates it until every job ended.
+    pub fn run(self) -> BakeReport {
+        let mut app = App::new();
+        app.add_plugins((
+            TaskPoolPlugin::default(),
+            TypeRegistrationPlugin,
+            FrameCountPlugin,
+            PooledTaskPlugin,
+        ));
+        for configure in self.configure {
+            configure(&mut app);
+        }
+        app.finish();
+        app.cleanup();
+
+        let world = &mut app.world;
+        let entities: Vec<Entity> = world
+            .entities()
+            .reserve_entities(self.capacity as u32)
+            .collect();
+        let pool = EntityPool::new(entities, world);
+        world.insert_resource(pool);
+        world.resource_mut::<PooledTasks>().set_seed(self.seed);
+
+        let mut runs: Vec<JobRun> = self
+            .jobs
+            .into_iter()
+            .map(|job| JobRun {
+                outcome: (job.slots > self.capacity).then_some(BakeOutcome::TooLarge),
+                name: job.name,
+                slots: job.slots,
+                task: Some(job.task),
+                on_finished: job.on_finished,
+                id: None,
+                seed: None,
+                started_frame: None,
+                started_at: None,
+                duration: Duration::ZERO,
+            })
+            .collect();
+
+        let mut finished = ManualEventReader::<PooledTaskFinished>::default();
+        let mut rejected = ManualEventReader::<PooledTaskRejected>::default();
+        let started_at = Instant::now();
+        let mut frames = 0;
+        let mut peak_in_use = 0;
+        let mut occupancy_sum = 0.0;
+
+        while runs.iter().any(|run| run.outcome.is_none()) {
+            if self
+                .timeout
+                .is_some_and(|timeout| started_at.elapsed() >= timeout)
+            {
+                let mut tasks = app.world.resource_mut::<PooledTasks>();
+                for run in runs.iter_mut().filter(|run| run.outcome.is_none()) {
+                    if let Some(id) = run.id {
+                        tasks.cancel(id);
+                    }
+                    run.outcome = Some(BakeOutcome::TimedOut);
+                }
+                break;
+            }
+
+            start_jobs(&mut app.world, &mut runs, frames);
+            app.update();
+            frames += 1;
+
+            let now = Instant::now();
+            let mut outcomes: HashMap<PooledTaskId, BakeOutcome> = HashMap::default();
+            let events = app.world.resource::<Events<PooledTaskFinished>>();
+            for event in finished.read(events) {
+                outcomes.insert(event.id, BakeOutcome::Finished);
+            }
+            let events = app.world.resource::<Events<PooledTaskRejected>>();
+            for event in rejected.read(events) {
+                outcomes.insert(event.id, BakeOutcome::Rejected(event.report.to_string()));
+            }
+            for run in runs.iter_mut().filter(|run| run.outcome.is_none()) {
+                run.poll(&mut app.world, &mut outcomes, now);
+            }
+
+            let stats = app.world.resource::<EntityPool>().stats();
+            peak_in_use = peak_in_use.max(stats.in_use);
+            occupancy_sum += crate::stats::occupancy(stats.in_use, stats.capacity);
+
+            if self.frame_interval.is_zero() {
+                std::thread::yield_now();
+            } else {
+                std::thread::sleep(self.frame_interval);
+            }
+        }
+
+        BakeReport {
+            capacity: self.capacity,
+            frames,
+            duration: started_at.elapsed(),
+            peak_in_use,
+            mean_occupancy: if frames == 0 {
+                0.0
+            } else {
+                occupancy_sum / frames as f32
+            },
+            jobs: runs
+                .into_iter()
+                .map(|run| BakeJobReport {
+                    name: run.name,
+                    slots: run.slots,
+                    seed: run.seed,
+                    outcome: run.outcome.unwrap_or(BakeOutcome::TimedOut),
+                    started_frame: run.started_frame,
+                    duration: run.duration,
+                })
+                .collect(),
+        }
+    }
+}
+
+struct JobRun {
+    name: String,
+    slots: usize,
+    /// Taken when the job is spawned.
+    task: Option<BakeTask>,
+    on_finished: Option<FinishedCallback>,
+    id: Option<PooledTaskId>,
+    seed: Option<u64>,
+    started_frame: Option<u64>,
+    started_at: Option<Instant>,
+    duration: Duration,
+    outcome: Option<BakeOutcome>,
+}
+
+impl JobRun {
+    /// Records the outcome of a spawned job once it ended.
+    fn poll(
+        &mut self,
+        world: &mut World,
+        outcomes: &mut HashMap<PooledTaskId, BakeOutcome>,
+        now: Instant,
+    ) {
+        let (Some(id), Some(started_at)) = (self.id, self.started_at) else {
+            return;
+        };
+
+        let mut tasks = world.resource_mut::<PooledTasks>();
+        let outcome = match outcomes.remove(&id) {
+            Some(outcome) => outcome,
+            // cancelled tasks vanish without an event
+            None if !tasks.is_running(id) => BakeOutcome::Cancelled,
+            None => return,
+        };
+        let handles = tasks.take_handles(id).unwrap_or_default();
+        self.duration = now.duration_since(started_at);
+
+        if outcome == BakeOutcome::Finished {
+            if let Some(on_finished) = self.on_finished.take() {
+                let entities: Vec<Entity> = handles.iter().map(|handle| handle.entity()).collect();
+                on_finished(world, &entities);
+            }
+        }
+        // dropping the handles returns the slots for the next jobs
+        drop(handles);
+        self.outcome = Some(outcome);
+    }
+}
+
+/// Spawns queued jobs in order while the pool has room for them.
+fn start_jobs(world: &mut World, runs: &mut [JobRun], frame: u64) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    world.resource_scope(|world, mut tasks: Mut<PooledTasks>| {
+        let mut pool = world.resource_mut::<EntityPool>();
+        let queued = runs
+            .iter_mut()
+            .filter(|run| run.outcome.is_none() && run.task.is_some());
+        for run in queued {
+            // keep jobs starting in order instead of letting small ones overtake
+            if run.slots > pool.capacity() - pool.stats().in_use {
+                break;
+            }
+            let task = run.task.take().expect("filtered above");
+
+            let id = tasks.spawn(&mut pool, &type_registry, run.slots, task);
+            run.id = Some(id);
+            run.seed = tasks.task_seed(id);
+            run.started_frame = Some(frame);
+            run.started_at = Some(Instant::now());
+        }
+    });
+}
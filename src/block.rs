@@ -0,0 +1,193 @@
+use crate::{EntityHandle, EntityPool};
+use bevy::{
+    ecs::{entity::Entity, world::World},
+    hierarchy::{BuildWorldChildren, Children, DespawnRecursiveExt, Parent},
+};
+use std::iter;
+
+/// Owns a contiguous group of pooled entities forming a small hierarchy - e.g. a chunk root plus
+/// its children.
+///
+/// The pool only records the intended shape. Call [`BlockHandle::build_hierarchy`] to insert the
+/// matching [`Parent`]/[`Children`] components in the world the entities are used in, and
+/// [`EntityPool::free_block`] to return the whole subtree at once. Dropping the block returns its
+/// entities individually like any other handle, without touching descendants spawned outside the
+/// pool.
+#[derive(Debug)]
+pub struct BlockHandle {
+    handles: Vec<EntityHandle>,
+    /// Index into `handles` of each entity's parent, `None` for the root.
+    parents: Vec<Option<usize>>,
+}
+
+impl BlockHandle {
+    /// Root of the block.
+    pub fn root(&self) -> Entity {
+        self.handles[0].entity()
+    }
+
+    /// All entities in the block in breadth-first order, starting with the root.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.handles.iter().map(EntityHandle::entity)
+    }
+
+    /// Handles of all entities in the block in breadth-first order.
+    pub fn handles(&self) -> &[EntityHandle] {
+        &self.handles
+    }
+
+    /// Number of entities in the block.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Always `false` - blocks contain at least their root.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Intended parent of the `index`th entity of the block, `None` for the root.
+    pub fn parent_of(&self, index: usize) -> Option<Entity> {
+        self.parents[index].map(|parent| self.handles[parent].entity())
+    }
+
+    /// Inserts [`Parent`]/[`Children`] components for the block's shape into `world`.
+    ///
+    /// # Panics
+    /// Panics if the entities don't exist in `world`.
+    pub fn build_hierarchy(&self, world: &mut World) {
+        for (index, handle) in self.handles.iter().enumerate().skip(1) {
+            let parent = self.parent_of(index).expect("only the root has no parent");
+            world.entity_mut(handle.entity()).set_parent(parent);
+        }
+    }
+}
+
+impl EntityPool {
+    /// Checks out `count` contiguous entities as a root (the first entity) with `count - 1` direct
+    /// children.
+    ///
+    /// # Panics
+    /// Panics if `count` is zero or there is no free run of `count` slots.
+    #[track_caller]
+    pub fn get_block(&mut self, count: usize) -> BlockHandle {
+        let Some(block) = self.try_get_block(count) else {
+            self.exhausted(&format!("no {count} contiguous entities available"));
+        };
+        block
+    }
+
+    /// Like [`EntityPool::get_block`] but returns `None` if there is no free run of `count` slots.
+    ///
+    /// # Panics
+    /// Panics if `count` is zero.
+    #[track_caller]
+    pub fn try_get_block(&mut self, count: usize) -> Option<BlockHandle> {
+        assert!(count > 0, "blocks need at least a root entity");
+
+        let parents = iter::once(None)
+            .chain(iter::repeat_n(Some(0), count - 1))
+            .collect();
+        self.checkout_block(parents)
+    }
+
+    /// Checks out a contiguous block shaped as a tree. `root_children[i]` is the number of children
+    /// of the `i`th entity in breadth-first order, e.g. `&[2, 0, 3]` is a root with two children, the
+    /// second of which has three children of its own. Entities past the end of `root_children` have
+    /// no children, and zero entries past the last entity are ignored.
+    ///
+    /// # Panics
+    /// Panics if `root_children` describes children of entities that don't exist or there is no free
+    /// run of slots large enough.
+    #[track_caller]
+    pub fn get_tree(&mut self, root_children: &[usize]) -> BlockHandle {
+        let Some(block) = self.try_get_tree(root_children) else {
+            self.exhausted("no contiguous entities available for tree");
+        };
+        block
+    }
+
+    /// Like [`EntityPool::get_tree`] but returns `None` if there is no free run of slots large
+    /// enough.
+    ///
+    /// # Panics
+    /// Panics if `root_children` describes children of entities that don't exist.
+    #[track_caller]
+    pub fn try_get_tree(&mut self, root_children: &[usize]) -> Option<BlockHandle> {
+        let mut parents = vec![None];
+        for (node, &children) in root_children.iter().enumerate() {
+            assert!(
+                node < parents.len() || children == 0,
+                "tree describes children of entity {node} but only has {} entities",
+                parents.len()
+            );
+            parents.extend(iter::repeat_n(Some(node), children));
+        }
+        self.checkout_block(parents)
+    }
+
+    /// Returns every entity of `block` to the pool at once.
+    ///
+    /// Follows `despawn_recursive` semantics: the block is detached from any parent outside of it,
+    /// descendants that aren't part of the block are despawned, and all pooled entities are cleared.
+    pub fn free_block(&mut self, block: BlockHandle, world: &mut World) {
+        self.debug_assert_world(world);
+
+        let pooled: Vec<Entity> = block.entities().collect();
+        for &entity in &pooled {
+            let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+                continue;
+            };
+
+            if entity_mut
+                .get::<Parent>()
+                .is_some_and(|parent| !pooled.contains(&parent.get()))
+            {
+                entity_mut.remove_parent();
+            }
+
+            let foreign_children: Vec<Entity> = entity_mut
+                .get::<Children>()
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|child| !pooled.contains(child))
+                .collect();
+            for child in foreign_children {
+                world.entity_mut(child).despawn_recursive();
+            }
+        }
+
+        for handle in block.handles {
+            self.release_handle(handle, world);
+        }
+    }
+
+    #[track_caller]
+    fn checkout_block(&mut self, parents: Vec<Option<usize>>) -> Option<BlockHandle> {
+        let Some(start) = self.find_free_run(parents.len()) else {
+            self.record_failure();
+            return None;
+        };
+
+        let end = start + parents.len() as u32;
+        assert!(
+            self.allocator.take_range(start..end),
+            "allocator didn't report free slots {start}..{end} as free"
+        );
+        let handles = (start..end).map(|slot| self.checkout(slot)).collect();
+
+        Some(BlockHandle { handles, parents })
+    }
+
+    fn find_free_run(&self, len: usize) -> Option<u32> {
+        let mut run = 0;
+        for (slot, &in_use) in self.in_use.iter().enumerate() {
+            run = if in_use { 0 } else { run + 1 };
+            if run == len {
+                return Some((slot + 1 - len) as u32);
+            }
+        }
+        None
+    }
+}
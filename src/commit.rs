@@ -0,0 +1,47 @@
+use crate::{release_quarantine, strip_isolation, EntityHandle, EntityPool};
+use bevy::ecs::{
+    entity::Entity,
+    event::{Event, Events},
+    world::World,
+};
+
+/// Sent by [`EntityPool::commit`] once a group of in-world scratch entities went live.
+///
+/// Registered by [`ScratchIsolationPlugin`](crate::ScratchIsolationPlugin) and
+/// [`QuarantinePlugin`](crate::QuarantinePlugin), or manually through `App::add_event`.
+#[derive(Event, Clone, Debug)]
+pub struct SlotsCommitted {
+    pub entities: Vec<Entity>,
+}
+
+impl EntityPool {
+    /// Flips a group of prepared in-world scratch entities to live in one exclusive operation, so
+    /// partially built structures never appear for a frame.
+    ///
+    /// Strips [`ScratchIsolation`](crate::ScratchIsolation), releases
+    /// [`Quarantine`](crate::Quarantine)d components and sends [`SlotsCommitted`] if the event is
+    /// registered. The handles stay checked out.
+    ///
+    /// # Panics
+    /// Panics before touching any entity if one of the handles is stale or belongs to another pool.
+    pub fn commit(&mut self, world: &mut World, handles: &[EntityHandle]) {
+        self.debug_assert_world(world);
+
+        for handle in handles {
+            assert!(
+                self.resolve(handle.key()) == Some(handle.entity()),
+                "can't commit {handle:?} - handle is stale or from another pool"
+            );
+        }
+
+        let entities: Vec<Entity> = handles.iter().map(EntityHandle::entity).collect();
+        for &entity in &entities {
+            strip_isolation(world, entity);
+            release_quarantine(world, entity);
+        }
+
+        if world.contains_resource::<Events<SlotsCommitted>>() {
+            world.send_event(SlotsCommitted { entities });
+        }
+    }
+}
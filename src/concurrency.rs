@@ -0,0 +1,91 @@
+use crate::{EntityPool, PoolStats};
+
+/// Bounds for adapting the number of concurrently running pooled tasks to pool pressure, see
+/// [`EntityPool::set_task_concurrency`].
+///
+/// Every frame [`PooledTaskPlugin`](crate::PooledTaskPlugin) halves the limit while the pool is
+/// under pressure - smoothed occupancy at or above `pressure`, or acquisitions failing - and raises
+/// it by one once occupancy fell to `relief`. Tasks over the limit wait in a queue.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TaskConcurrency {
+    pub min: usize,
+    pub max: usize,
+    pub pressure: f32,
+    pub relief: f32,
+}
+
+impl TaskConcurrency {
+    /// # Panics
+    /// Panics if `min` is 0 or greater than `max`.
+    pub fn new(min: usize, max: usize) -> Self {
+        assert!(
+            0 < min && min <= max,
+            "concurrency bounds must satisfy 0 < min <= max, got {min}..={max}"
+        );
+        Self {
+            min,
+            max,
+            pressure: 0.85,
+            relief: 0.6,
+        }
+    }
+
+    /// Sets the occupancy thresholds.
+    ///
+    /// # Panics
+    /// Panics if `relief` isn't below `pressure` or either is outside of `0.0..=1.0`.
+    pub fn with_thresholds(mut self, pressure: f32, relief: f32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&relief) && relief < pressure && pressure <= 1.0,
+            "thresholds must satisfy 0 <= relief < pressure <= 1, got {relief} and {pressure}"
+        );
+        self.pressure = pressure;
+        self.relief = relief;
+        self
+    }
+}
+
+/// Current limit of a pool's tasks.
+#[derive(Default)]
+pub(crate) struct ConcurrencyController {
+    limit: Option<usize>,
+    failed_acquisitions: u64,
+}
+
+impl ConcurrencyController {
+    pub(crate) fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    pub(crate) fn update(&mut self, config: Option<&TaskConcurrency>, stats: &PoolStats) {
+        let failed = stats.failed_acquisitions > self.failed_acquisitions;
+        self.failed_acquisitions = stats.failed_acquisitions;
+
+        let Some(config) = config else {
+            self.limit = None;
+            return;
+        };
+
+        let limit = self.limit.unwrap_or(config.max);
+        let limit = if failed || stats.smoothed_occupancy >= config.pressure {
+            limit / 2
+        } else if stats.smoothed_occupancy <= config.relief {
+            limit + 1
+        } else {
+            limit
+        };
+        self.limit = Some(limit.clamp(config.min, config.max));
+    }
+}
+
+impl EntityPool {
+    /// Adapts the number of concurrently running pooled tasks to this pool's pressure within
+    /// `concurrency`'s bounds. `None`, the default, runs every task right away.
+    pub fn set_task_concurrency(&mut self, concurrency: Option<TaskConcurrency>) {
+        self.task_concurrency = concurrency;
+    }
+
+    pub fn task_concurrency(&self) -> Option<&TaskConcurrency> {
+        self.task_concurrency.as_ref()
+    }
+}
@@ -0,0 +1,281 @@
+use crate::EntityPool;
+use bevy::ecs::{
+    entity::Entity,
+    reflect::AppTypeRegistry,
+    system::Resource,
+    world::{Mut, World},
+};
+use std::any::TypeId;
+
+/// Identifies a scratch world mirrored by a [`PoolCoordinator`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MirrorId(usize);
+
+/// Keeps the logical pool of the main world's [`EntityPool`] resource mirrored across any number
+/// of long-lived scratch worlds.
+///
+/// Every mirror has the pool's entities spawned under the same ids as in the main world. Slots the
+/// pool clears - through the coordinator, dropped handles, [`EntityPool::release`] or
+/// [`EntityPool::reset`] - are cleared (and reset to the pool's template) in every mirror on the
+/// next [`PoolCoordinator::sync`], which [`PooledTaskPlugin`](crate::PooledTaskPlugin) runs every
+/// frame. So do entities the pool grew by, e.g. through [`EntityPool::reserve`].
+#[derive(Resource, Default)]
+pub struct PoolCoordinator {
+    mirrors: Vec<World>,
+    /// Number of pool entities spawned in every mirror.
+    capacity: usize,
+}
+
+/// Result of [`PoolCoordinator::audit`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoordinatorAudit {
+    /// Pooled entities missing from the main world.
+    pub missing_in_main: Vec<Entity>,
+    /// Pooled entities missing from each mirror.
+    pub missing_in_mirrors: Vec<(MirrorId, Vec<Entity>)>,
+    /// Free slots whose entity has other component types in a mirror than in the main world. In
+    /// use slots are left out, their owners may work on them in any world.
+    pub diverged_in_mirrors: Vec<(MirrorId, Vec<Entity>)>,
+}
+
+impl CoordinatorAudit {
+    /// Returns `true` when every world contains every pooled entity, with matching components on
+    /// free slots.
+    pub fn is_consistent(&self) -> bool {
+        self.missing_in_main.is_empty()
+            && self.missing_in_mirrors.is_empty()
+            && self.diverged_in_mirrors.is_empty()
+    }
+}
+
+impl PoolCoordinator {
+    /// Creates a scratch world mirroring `pool`, sharing the main world's type registry if present.
+    ///
+    /// # Panics
+    /// Panics if `pool` doesn't belong to `main` or it isn't possible to spawn all entities.
+    pub fn add_mirror(&mut self, pool: &mut EntityPool, main: &World) -> MirrorId {
+        assert!(
+            pool.belongs_to(main),
+            "{:?} doesn't belong to the main world {:?}",
+            pool.id(),
+            main.id()
+        );
+
+        let mut mirror = World::new();
+        if let Some(type_registry) = main.get_resource::<AppTypeRegistry>() {
+            mirror.insert_resource(type_registry.clone());
+        }
+        if self.mirrors.is_empty() {
+            self.capacity = pool.capacity();
+        }
+        // anything the pool grew by since the last sync is added to all mirrors at once
+        spawn_in(&mut mirror, &pool.entities()[..self.capacity]);
+        for slot in 0..self.capacity as u32 {
+            pool.apply_template_to(slot, pool.entity(slot), &mut mirror);
+        }
+        pool.track_frees();
+
+        self.mirrors.push(mirror);
+        MirrorId(self.mirrors.len() - 1)
+    }
+
+    /// Returns the mirror world.
+    pub fn mirror(&self, id: MirrorId) -> &World {
+        &self.mirrors[id.0]
+    }
+
+    /// Returns the mirror world mutably.
+    pub fn mirror_mut(&mut self, id: MirrorId) -> &mut World {
+        &mut self.mirrors[id.0]
+    }
+
+    /// Iterates over all mirrors.
+    pub fn mirrors_mut(&mut self) -> impl Iterator<Item = (MirrorId, &mut World)> {
+        self.mirrors
+            .iter_mut()
+            .enumerate()
+            .map(|(index, world)| (MirrorId(index), world))
+    }
+
+    /// Frees `slot` in the main world's pool (invalidating its handle) and clears the slot's entity
+    /// in every mirror.
+    ///
+    /// # Panics
+    /// Panics if `main` has no [`EntityPool`] resource or `slot` is out of range.
+    pub fn free_slot(&mut self, main: &mut World, slot: u32) {
+        main.resource_scope(|main, mut pool: Mut<EntityPool>| {
+            pool.free_slot(slot, main);
+            self.sync_pool(&mut pool);
+        });
+    }
+
+    /// Frees every slot in the main world's pool and clears the pooled entities in every mirror.
+    ///
+    /// # Panics
+    /// Panics if `main` has no [`EntityPool`] resource.
+    pub fn free_all(&mut self, main: &mut World) {
+        main.resource_scope(|main, mut pool: Mut<EntityPool>| {
+            pool.free_entities(main);
+            // slots that were already free are cleared in the mirrors too
+            for slot in 0..pool.capacity() as u32 {
+                pool.record_freed(slot);
+            }
+            self.sync_pool(&mut pool);
+        });
+    }
+
+    /// Grows the main world's pool by `entities` and reserves them in every mirror.
+    ///
+    /// # Panics
+    /// Panics if `main` has no [`EntityPool`] resource or it isn't possible to spawn all entities.
+    /// Mirrors are checked first, nothing changes in any world if they can't take the entities.
+    pub fn grow(&mut self, main: &mut World, entities: Vec<Entity>) {
+        for mirror in &self.mirrors {
+            check_spawnable(mirror, &entities);
+        }
+        main.resource_scope(|main, mut pool: Mut<EntityPool>| {
+            pool.grow(entities, main);
+            self.sync_pool(&mut pool);
+        });
+    }
+
+    /// Mirrors the slots the main world's pool cleared, and the entities it grew by, since the
+    /// last sync.
+    ///
+    /// # Panics
+    /// Panics if `main` has no [`EntityPool`] resource or grown entities can't be spawned in a
+    /// mirror.
+    pub fn sync(&mut self, main: &mut World) {
+        main.resource_scope(|_, mut pool: Mut<EntityPool>| self.sync_pool(&mut pool));
+    }
+
+    fn sync_pool(&mut self, pool: &mut EntityPool) {
+        let freed = pool.take_freed();
+        if self.mirrors.is_empty() {
+            return;
+        }
+
+        let grown = &pool.entities()[self.capacity.min(pool.capacity())..];
+        for mirror in &self.mirrors {
+            check_spawnable(mirror, grown);
+        }
+        for mirror in &mut self.mirrors {
+            spawn_in(mirror, grown);
+            for slot in self.capacity as u32..pool.capacity() as u32 {
+                pool.apply_template_to(slot, pool.entity(slot), mirror);
+            }
+        }
+        self.capacity = pool.capacity();
+
+        for slot in freed {
+            let entity = pool.entity(slot);
+            for mirror in &mut self.mirrors {
+                if let Some(mut entity) = mirror.get_entity_mut(entity) {
+                    entity.retain::<()>();
+                    pool.apply_template_to(slot, entity.id(), mirror);
+                }
+            }
+        }
+    }
+
+    /// Checks that every pooled entity exists in the main world and every mirror, and that free
+    /// slots have the same component types everywhere.
+    ///
+    /// # Panics
+    /// Panics if `main` has no [`EntityPool`] resource.
+    pub fn audit(&self, main: &World) -> CoordinatorAudit {
+        let pool = main.resource::<EntityPool>();
+        let missing_in = |world: &World| -> Vec<Entity> {
+            pool.entities()
+                .iter()
+                .copied()
+                .filter(|&entity| world.get_entity(entity).is_none())
+                .collect()
+        };
+        let diverged_in = |world: &World| -> Vec<Entity> {
+            (0..pool.capacity() as u32)
+                .filter(|&slot| !pool.in_use[slot as usize])
+                .map(|slot| pool.entity(slot))
+                .filter(|&entity| {
+                    let (Some(main_types), Some(mirror_types)) = (
+                        component_types(main, entity),
+                        component_types(world, entity),
+                    ) else {
+                        return false;
+                    };
+                    main_types != mirror_types
+                })
+                .collect()
+        };
+
+        let per_mirror = |check: &dyn Fn(&World) -> Vec<Entity>| -> Vec<(MirrorId, Vec<Entity>)> {
+            self.mirrors
+                .iter()
+                .enumerate()
+                .map(|(index, mirror)| (MirrorId(index), check(mirror)))
+                .filter(|(_, entities)| !entities.is_empty())
+                .collect()
+        };
+        CoordinatorAudit {
+            missing_in_main: missing_in(main),
+            missing_in_mirrors: per_mirror(&missing_in),
+            diverged_in_mirrors: per_mirror(&diverged_in),
+        }
+    }
+}
+
+impl EntityPool {
+    /// Starts recording cleared slots for [`PoolCoordinator::sync`].
+    pub(crate) fn track_frees(&mut self) {
+        self.freed.get_or_insert_with(Vec::new);
+    }
+
+    pub(crate) fn record_freed(&mut self, slot: u32) {
+        if let Some(freed) = &mut self.freed {
+            freed.push(slot);
+        }
+    }
+
+    pub(crate) fn take_freed(&mut self) -> Vec<u32> {
+        self.freed.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+}
+
+/// Mirrors the main world's pool, see [`PoolCoordinator::sync`].
+pub fn sync_pool_coordinator(world: &mut World) {
+    world.resource_scope(|world, mut coordinator: Mut<PoolCoordinator>| coordinator.sync(world));
+}
+
+/// Sorted component types of `entity`, compared across worlds whose component ids differ.
+fn component_types(world: &World, entity: Entity) -> Option<Vec<TypeId>> {
+    let entity = world.get_entity(entity)?;
+    let mut types: Vec<TypeId> = entity
+        .archetype()
+        .components()
+        .filter_map(|id| world.components().get_info(id)?.type_id())
+        .collect();
+    types.sort_unstable();
+    Some(types)
+}
+
+/// Panics unless `entities` can be spawned in `world` under their ids.
+fn check_spawnable(world: &World, entities: &[Entity]) {
+    for &entity in entities {
+        let taken = world.get_entity(entity).is_none()
+            && world
+                .entities()
+                .resolve_from_id(entity.index())
+                .is_some_and(|occupant| world.get_entity(occupant).is_some());
+        assert!(
+            !taken,
+            "can't mirror pooled entity {entity:?} - its index is taken in {:?}",
+            world.id()
+        );
+    }
+}
+
+fn spawn_in(world: &mut World, entities: &[Entity]) {
+    if let Err(e) = world.insert_or_spawn_batch(entities.iter().copied().map(|e| (e, ()))) {
+        panic!("Failed to spawn all entities {e:?}");
+    }
+}
@@ -0,0 +1,124 @@
+use crate::PooledTaskId;
+use bevy::{
+    ecs::{entity::Entity, world::World},
+    reflect::Reflect,
+    scene::DynamicEntity,
+    utils::HashMap,
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+type DuplicateHandler = Box<dyn FnMut(&mut World, DuplicateSlot) + Send + Sync>;
+
+/// Content-hashing pass over a pooled task's results during copy-back, see
+/// [`PooledTasks::set_copy_back_dedup`](crate::PooledTasks::set_copy_back_dedup).
+///
+/// Slots whose components have the same values as an earlier slot of the same task aren't
+/// committed - their main-world entities are left empty and handed to the callback instead, which
+/// can point them at a shared asset or entity (e.g. insert a component referencing
+/// [`DuplicateSlot::original`]). Slots without components, or with components that can't be
+/// compared through reflection, are never treated as duplicates.
+pub struct CopyBackDedup {
+    on_duplicate: DuplicateHandler,
+}
+
+/// A copied back slot with the same content as an earlier slot of its task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DuplicateSlot {
+    pub task: PooledTaskId,
+    /// Main-world entity that was left empty.
+    pub entity: Entity,
+    /// Main-world entity the identical content was committed to.
+    pub original: Entity,
+    /// Content hash shared by both slots.
+    pub hash: u64,
+}
+
+impl CopyBackDedup {
+    /// Calls `on_duplicate` with the main world for every duplicate, after the task's unique slots
+    /// were merged.
+    pub fn new(
+        on_duplicate: impl FnMut(&mut World, DuplicateSlot) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            on_duplicate: Box::new(on_duplicate),
+        }
+    }
+
+    /// Splits extracted slots into those to commit and their duplicates.
+    pub(crate) fn split(
+        &self,
+        task: PooledTaskId,
+        entities: Vec<DynamicEntity>,
+    ) -> (Vec<DynamicEntity>, Vec<DuplicateSlot>) {
+        let mut unique: Vec<DynamicEntity> = Vec::with_capacity(entities.len());
+        let mut by_hash: HashMap<u64, Vec<usize>> = HashMap::default();
+        let mut duplicates = Vec::new();
+
+        for entity in entities {
+            if entity.components.is_empty() {
+                unique.push(entity);
+                continue;
+            }
+
+            let hash = content_hash(&entity.components);
+            let candidates = by_hash.entry(hash).or_default();
+            // hashes only narrow it down, the first slot with equal values wins
+            let original = candidates
+                .iter()
+                .map(|&index| &unique[index])
+                .find(|original| same_content(&original.components, &entity.components));
+            match original {
+                Some(original) => duplicates.push(DuplicateSlot {
+                    task,
+                    entity: entity.entity,
+                    original: original.entity,
+                    hash,
+                }),
+                None => {
+                    candidates.push(unique.len());
+                    unique.push(entity);
+                }
+            }
+        }
+        (unique, duplicates)
+    }
+
+    pub(crate) fn resolve(&mut self, world: &mut World, duplicates: Vec<DuplicateSlot>) {
+        for duplicate in duplicates {
+            (self.on_duplicate)(world, duplicate);
+        }
+    }
+}
+
+/// Order-independent hash over the reflected values - extracted components are dynamic types
+/// without `reflect_hash`, their debug output names the represented type and every field. Opaque
+/// values all print the same, [`same_content`] tells them apart.
+fn content_hash(components: &[Box<dyn Reflect>]) -> u64 {
+    let mut parts: Vec<String> = components
+        .iter()
+        .map(|component| format!("{component:?}"))
+        .collect();
+    parts.sort_unstable();
+
+    let mut hasher = DefaultHasher::new();
+    parts.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Values that can't be compared through reflection never count as equal.
+fn same_content(a: &[Box<dyn Reflect>], b: &[Box<dyn Reflect>]) -> bool {
+    let type_path = |component: &dyn Reflect| {
+        component
+            .get_represented_type_info()
+            .map(|info| info.type_path())
+    };
+    a.len() == b.len()
+        && a.iter().all(|a| {
+            b.iter().any(|b| {
+                type_path(&**a) == type_path(&**b) && a.reflect_partial_eq(&**b).unwrap_or(false)
+            })
+        })
+}
@@ -0,0 +1,142 @@
+use crate::{EntityPool, SlotKey};
+use bevy::{
+    app::{App, Plugin, Update},
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    ecs::{
+        entity::Entity,
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::Res,
+    },
+};
+use std::panic::Location;
+
+/// An entity that was still checked out when [`EntityPool::free_entities`] reclaimed it.
+///
+/// Only recorded while leak detection is enabled, see [`EntityPool::set_leak_detection`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeakReport {
+    pub key: SlotKey,
+    pub entity: Entity,
+    /// Call site of the [`EntityPool::get`] that checked the entity out.
+    pub acquired_at: &'static Location<'static>,
+}
+
+/// Registers the statistics of the app's [`EntityPool`] resource with `bevy_diagnostic`.
+///
+/// Measurements are taken every frame in [`Update`] while the resource exists. Apps with several
+/// primary worlds (e.g. a server and a client in one process) should use
+/// [`EntityPoolDiagnosticsPlugin::labeled`] so the worlds' diagnostics can be told apart.
+#[derive(Default)]
+pub struct EntityPoolDiagnosticsPlugin {
+    paths: PoolDiagnosticPaths,
+}
+
+impl EntityPoolDiagnosticsPlugin {
+    pub const IN_USE: DiagnosticPath = DiagnosticPath::const_new("entity_pool/in_use");
+    pub const PEAK_IN_USE: DiagnosticPath = DiagnosticPath::const_new("entity_pool/peak_in_use");
+    pub const ACQUISITIONS: DiagnosticPath =
+        DiagnosticPath::const_new("entity_pool/total_acquisitions");
+    pub const FAILED_ACQUISITIONS: DiagnosticPath =
+        DiagnosticPath::const_new("entity_pool/failed_acquisitions");
+    pub const OCCUPANCY: DiagnosticPath = DiagnosticPath::const_new("entity_pool/occupancy");
+
+    /// Reports under `{label}/entity_pool/...` instead of `entity_pool/...`.
+    pub fn labeled(label: &str) -> Self {
+        Self {
+            paths: PoolDiagnosticPaths::labeled(label),
+        }
+    }
+
+    pub fn paths(&self) -> &PoolDiagnosticPaths {
+        &self.paths
+    }
+}
+
+impl Plugin for EntityPoolDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let paths = self.paths.clone();
+        paths.register(app);
+        app.add_systems(
+            Update,
+            (move |mut diagnostics: Diagnostics, pool: Res<EntityPool>| {
+                paths.measure(&mut diagnostics, &pool)
+            })
+            .run_if(resource_exists::<EntityPool>),
+        );
+    }
+}
+
+/// Diagnostic paths a pool's statistics are reported under.
+///
+/// Also usable without the plugin, e.g. to report the pool of a secondary world into the main
+/// app's diagnostics - [`PoolDiagnosticPaths::register`] them once, then
+/// [`PoolDiagnosticPaths::measure`] every frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolDiagnosticPaths {
+    pub in_use: DiagnosticPath,
+    pub peak_in_use: DiagnosticPath,
+    pub acquisitions: DiagnosticPath,
+    pub failed_acquisitions: DiagnosticPath,
+    pub occupancy: DiagnosticPath,
+}
+
+impl Default for PoolDiagnosticPaths {
+    fn default() -> Self {
+        Self {
+            in_use: EntityPoolDiagnosticsPlugin::IN_USE,
+            peak_in_use: EntityPoolDiagnosticsPlugin::PEAK_IN_USE,
+            acquisitions: EntityPoolDiagnosticsPlugin::ACQUISITIONS,
+            failed_acquisitions: EntityPoolDiagnosticsPlugin::FAILED_ACQUISITIONS,
+            occupancy: EntityPoolDiagnosticsPlugin::OCCUPANCY,
+        }
+    }
+}
+
+impl PoolDiagnosticPaths {
+    /// The default paths prefixed with `label`.
+    pub fn labeled(label: &str) -> Self {
+        let prefix = |path: DiagnosticPath| {
+            DiagnosticPath::from_components(std::iter::once(label).chain(path.components()))
+        };
+        let defaults = Self::default();
+        Self {
+            in_use: prefix(defaults.in_use),
+            peak_in_use: prefix(defaults.peak_in_use),
+            acquisitions: prefix(defaults.acquisitions),
+            failed_acquisitions: prefix(defaults.failed_acquisitions),
+            occupancy: prefix(defaults.occupancy),
+        }
+    }
+
+    /// Registers the paths with `app`'s diagnostics store.
+    pub fn register(&self, app: &mut App) {
+        // counters are already exact, the pool does its own smoothing for occupancy
+        app.register_diagnostic(Diagnostic::new(self.in_use.clone()).with_smoothing_factor(0.0))
+            .register_diagnostic(
+                Diagnostic::new(self.peak_in_use.clone()).with_smoothing_factor(0.0),
+            )
+            .register_diagnostic(
+                Diagnostic::new(self.acquisitions.clone()).with_smoothing_factor(0.0),
+            )
+            .register_diagnostic(
+                Diagnostic::new(self.failed_acquisitions.clone()).with_smoothing_factor(0.0),
+            )
+            .register_diagnostic(
+                Diagnostic::new(self.occupancy.clone())
+                    .with_suffix("%")
+                    .with_smoothing_factor(0.0),
+            );
+    }
+
+    /// Records `pool`'s current statistics.
+    pub fn measure(&self, diagnostics: &mut Diagnostics, pool: &EntityPool) {
+        let stats = pool.stats();
+        diagnostics.add_measurement(&self.in_use, || stats.in_use as f64);
+        diagnostics.add_measurement(&self.peak_in_use, || stats.peak_in_use as f64);
+        diagnostics.add_measurement(&self.acquisitions, || stats.total_acquisitions as f64);
+        diagnostics.add_measurement(&self.failed_acquisitions, || {
+            stats.failed_acquisitions as f64
+        });
+        diagnostics.add_measurement(&self.occupancy, || stats.smoothed_occupancy as f64 * 100.0);
+    }
+}
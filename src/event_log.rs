@@ -0,0 +1,142 @@
+use crate::EntityPool;
+use bevy::utils::{Duration, Instant};
+use std::{borrow::Cow, collections::VecDeque, fmt::Write};
+
+/// Operation recorded in a pool's event log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolOp {
+    Acquire {
+        slot: u32,
+    },
+    Free {
+        slot: u32,
+    },
+    Grow {
+        added: usize,
+    },
+    /// An acquisition failed because the pool was exhausted.
+    Fail,
+    /// [`EntityPool::next_generation`] started a new pool generation.
+    NextGeneration {
+        generation: u64,
+    },
+}
+
+/// Entry of a pool's event log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolLogEntry {
+    /// Time since the log was enabled.
+    pub at: Duration,
+    pub op: PoolOp,
+    /// Label set with [`EntityPool::set_log_label`] when the operation happened.
+    pub label: Option<Cow<'static, str>>,
+    /// Slots in use right after the operation.
+    pub in_use: usize,
+}
+
+/// Fixed size ring buffer of the last pool operations.
+pub(crate) struct EventLog {
+    entries: VecDeque<PoolLogEntry>,
+    capacity: usize,
+    started: Instant,
+    label: Option<Cow<'static, str>>,
+}
+
+impl EventLog {
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.started = Instant::now();
+        self.label = None;
+    }
+
+    pub(crate) fn record(&mut self, op: PoolOp, in_use: usize) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(PoolLogEntry {
+            at: self.started.elapsed(),
+            op,
+            label: self.label.clone(),
+            in_use,
+        });
+    }
+}
+
+impl EntityPool {
+    /// Records the last `capacity` acquisitions, frees, grows, failed acquisitions and generation
+    /// rollovers so an error report can include the history that led to it - exhaustion panics
+    /// append the log. Replaces a previous log.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0.
+    pub fn enable_event_log(&mut self, capacity: usize) {
+        assert!(capacity > 0, "event log needs room for at least one entry");
+        self.event_log = Some(EventLog {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            started: Instant::now(),
+            label: None,
+        });
+    }
+
+    pub fn disable_event_log(&mut self) {
+        self.event_log = None;
+    }
+
+    /// Attaches `label` to operations logged from now on, e.g. the job causing them.
+    pub fn set_log_label(&mut self, label: Option<impl Into<Cow<'static, str>>>) {
+        if let Some(log) = &mut self.event_log {
+            log.label = label.map(Into::into);
+        }
+    }
+
+    /// Logged operations, oldest first. Empty if the log isn't enabled.
+    pub fn event_log(&self) -> impl Iterator<Item = &PoolLogEntry> + '_ {
+        self.event_log.iter().flat_map(|log| log.entries.iter())
+    }
+
+    /// Formats the event log one operation per line, oldest first.
+    pub fn dump_event_log(&self) -> String {
+        let mut dump = String::new();
+        for entry in self.event_log() {
+            let _ = write!(
+                dump,
+                "\n[{:>10.3}ms] {:?} in use {}",
+                entry.at.as_secs_f64() * 1000.0,
+                entry.op,
+                entry.in_use
+            );
+            if let Some(label) = &entry.label {
+                let _ = write!(dump, " ({label})");
+            }
+        }
+        dump
+    }
+
+    pub(crate) fn log(&mut self, op: PoolOp) {
+        let in_use = self.in_use_count();
+        if let Some(log) = &mut self.event_log {
+            log.record(op, in_use);
+        }
+    }
+
+    /// Records a failed acquisition. Every acquisition path reports exhaustion through it.
+    pub(crate) fn record_failure(&mut self) {
+        self.stats.record_failure();
+        self.ages.record_failure();
+        self.log(PoolOp::Fail);
+        self.warn_pending_reservations();
+    }
+
+    /// Panics with `reason`, followed by the event log if it's enabled.
+    #[track_caller]
+    pub(crate) fn exhausted(&self, reason: &str) -> ! {
+        if self.event_log.is_none() {
+            panic!("pool exhaustion - {reason}");
+        }
+        panic!(
+            "pool exhaustion - {reason}, recent operations:{}",
+            self.dump_event_log()
+        );
+    }
+}
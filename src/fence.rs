@@ -0,0 +1,35 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// One-shot signal marking the end of a pipeline stage. Clones observe the same signal.
+#[derive(Clone, Debug, Default)]
+pub struct Fence(Arc<AtomicBool>);
+
+impl Fence {
+    /// Returns `true` once the stage this fence guards has completed.
+    pub fn is_signaled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn signal(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+/// Fences between the stages of a pooled task, signaled in order.
+///
+/// - `copied_in` - main-world components were copied into the scratch world
+/// - `executed` - the task closure returned
+/// - `copied_back` - results were merged into the main world, the task's slots are safe to reuse
+///
+/// Wait on an earlier task's `copied_back` before copying in slots or data it produces - see
+/// [`PooledTasks::spawn_staged`](crate::PooledTasks::spawn_staged) - so copy-back of one stage can
+/// overlap with execution of the next without racing on pool slots.
+#[derive(Clone, Debug, Default)]
+pub struct PooledTaskFences {
+    pub copied_in: Fence,
+    pub executed: Fence,
+    pub copied_back: Fence,
+}
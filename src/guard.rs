@@ -0,0 +1,150 @@
+use crate::{EntityPool, PoolId};
+use bevy::{
+    app::{App, First, Plugin},
+    ecs::{
+        entity::Entity,
+        event::{Event, Events},
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        world::{Mut, World},
+    },
+    log::warn,
+};
+
+/// Sent by [`guard_entity_pool`] when pooled entities vanished from the world, e.g. because user
+/// code called `World::clear_entities`.
+#[derive(Event, Clone, Debug)]
+pub struct PoolEntitiesLost {
+    pub pool: PoolId,
+    /// Entities that were missing and have been reserved again.
+    pub recovered: Vec<Entity>,
+    /// Entities that were missing but couldn't be reserved again because another entity with the
+    /// same index was spawned in the meantime. Their slots stay out of circulation.
+    pub conflicts: Vec<Entity>,
+    /// Number of outstanding handles invalidated because their entity was lost.
+    pub invalidated: usize,
+}
+
+/// Result of [`EntityPool::recover`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolRecovery {
+    pub recovered: Vec<Entity>,
+    pub conflicts: Vec<Entity>,
+    pub invalidated: usize,
+}
+
+/// Opt-in safeguard against world-wide clears destroying the reserved address space.
+///
+/// At the start of every frame the app's [`EntityPool`] resource is audited; missing entities are
+/// reserved again, handles to them are invalidated and [`PoolEntitiesLost`] is sent. Entities
+/// spawned between the clear and the audit can take pooled indices - clear entities at the end of
+/// a frame, or call [`EntityPool::recover`] right after clearing, to avoid that.
+pub struct PoolGuardPlugin;
+
+impl Plugin for PoolGuardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PoolEntitiesLost>().add_systems(
+            First,
+            guard_entity_pool.run_if(resource_exists::<EntityPool>),
+        );
+    }
+}
+
+impl EntityPool {
+    /// Returns the reserved entities that no longer exist in `world`. Entities of conflicted slots
+    /// are only returned once their index is free to be reserved again.
+    pub fn audit(&self, world: &World) -> Vec<Entity> {
+        self.debug_assert_world(world);
+
+        (0..self.entities.len() as u32)
+            .filter(|&slot| self.is_lost(slot, world))
+            .map(|slot| self.entities[slot as usize])
+            .collect()
+    }
+
+    /// Reserves lost entities again. In use slots whose entity was lost are reclaimed, invalidating
+    /// their handles - whatever the handle owners wrote is gone with the entity.
+    ///
+    /// Slots whose index was taken by another entity stay out of circulation and are reported as
+    /// conflicts once - later calls skip them until the other entity is despawned.
+    pub fn recover(&mut self, world: &mut World) -> PoolRecovery {
+        self.debug_assert_world(world);
+
+        let mut recovery = PoolRecovery::default();
+        for slot in 0..self.entities.len() as u32 {
+            if !self.is_lost(slot, world) {
+                continue;
+            }
+            let entity = self.entities[slot as usize];
+
+            let conflicted = self.conflicted.contains(&slot);
+            if conflicted {
+                // the handle was already invalidated when the conflict was reported
+            } else if self.in_use[slot as usize] {
+                recovery.invalidated += 1;
+            } else {
+                // out of circulation until it's reserved again below
+                self.allocator.take(slot);
+            }
+            self.in_use[slot as usize] = true;
+
+            if world.get_or_spawn(entity).is_some() {
+                self.conflicted.retain(|&other| other != slot);
+                self.release_slot(slot, world);
+                recovery.recovered.push(entity);
+            } else {
+                // keep stale handles from resolving to the entity that took the index
+                self.generations[slot as usize] = self.generations[slot as usize].wrapping_add(1);
+                if let Some(acquired_at) = &mut self.acquired_at {
+                    acquired_at[slot as usize] = None;
+                }
+                self.conflicted.push(slot);
+                self.update_watermarks();
+                recovery.conflicts.push(entity);
+            }
+        }
+        recovery
+    }
+
+    /// Returns `true` if the slot's entity is gone and can be reserved again - conflicted slots
+    /// only count once the entity holding their index is despawned.
+    fn is_lost(&self, slot: u32, world: &World) -> bool {
+        let entity = self.entities[slot as usize];
+        if world.get_entity(entity).is_some() {
+            return false;
+        }
+        !self.conflicted.contains(&slot)
+            || world
+                .entities()
+                .resolve_from_id(entity.index())
+                .is_none_or(|current| world.get_entity(current).is_none())
+    }
+}
+
+/// Audits the [`EntityPool`] resource and recovers lost entities, see [`PoolGuardPlugin`].
+pub fn guard_entity_pool(world: &mut World) {
+    let lost = world.resource_scope(|world, mut pool: Mut<EntityPool>| {
+        if pool.audit(world).is_empty() {
+            return None;
+        }
+
+        let recovery = pool.recover(world);
+        warn!(
+            "{} pooled entities were lost, {} recovered, {} handles invalidated",
+            recovery.recovered.len() + recovery.conflicts.len(),
+            recovery.recovered.len(),
+            recovery.invalidated
+        );
+        Some(PoolEntitiesLost {
+            pool: pool.id(),
+            recovered: recovery.recovered,
+            conflicts: recovery.conflicts,
+            invalidated: recovery.invalidated,
+        })
+    });
+
+    if let Some(lost) = lost {
+        if world.contains_resource::<Events<PoolEntitiesLost>>() {
+            world.send_event(lost);
+        }
+    }
+}
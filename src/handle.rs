@@ -0,0 +1,81 @@
+use crate::SlotKey;
+use bevy::ecs::{component::Component, entity::Entity};
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Marker component requesting that a pooled entity be returned to its pool.
+///
+/// Inserting it (e.g. through `Commands` from a system that doesn't own the handle) has the same
+/// effect as dropping the entity's [`EntityHandle`] - the entity is cleared and made available again
+/// on the next [`EntityPool::reclaim`](crate::EntityPool::reclaim). The outstanding handle is
+/// invalidated and dropping it afterwards is a no-op.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Reclaim;
+
+/// Releases queued by dropped handles, drained by the owning pool.
+pub(crate) type ReleaseQueue = Arc<Mutex<Vec<SlotKey>>>;
+
+pub(crate) fn lock_releases(releases: &ReleaseQueue) -> MutexGuard<'_, Vec<SlotKey>> {
+    // a poisoned queue only means another handle panicked mid-push, the data is still sound
+    releases.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// RAII guard over a pooled entity.
+///
+/// Dropping the handle queues the entity for reclamation - no world access is needed, so handles
+/// can be dropped anywhere (including early returns in async tasks). The entity's components are
+/// cleared the next time the pool runs [`EntityPool::reclaim`](crate::EntityPool::reclaim).
+pub struct EntityHandle {
+    pub(crate) entity: Entity,
+    pub(crate) key: SlotKey,
+    /// `None` once the release was handled by the pool directly.
+    pub(crate) releases: Option<ReleaseQueue>,
+}
+
+impl EntityHandle {
+    /// The pooled entity this handle grants access to.
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Key identifying this checkout, see [`SlotKey::to_raw`] for handing it to scripting layers.
+    pub fn key(&self) -> SlotKey {
+        self.key
+    }
+}
+
+impl Deref for EntityHandle {
+    type Target = Entity;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entity
+    }
+}
+
+impl fmt::Debug for EntityHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntityHandle")
+            .field("entity", &self.entity)
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl EntityHandle {
+    /// Consumes the handle without queueing a release, for pools releasing the slot right away.
+    pub(crate) fn disarm(mut self) -> SlotKey {
+        self.releases = None;
+        self.key
+    }
+}
+
+impl Drop for EntityHandle {
+    fn drop(&mut self) {
+        if let Some(releases) = &self.releases {
+            lock_releases(releases).push(self.key);
+        }
+    }
+}
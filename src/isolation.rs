@@ -0,0 +1,179 @@
+use crate::{EntityHandle, EntityPool, SlotsCommitted};
+use bevy::{
+    app::{App, Plugin, PostUpdate},
+    ecs::{
+        change_detection::{DetectChanges, DetectChangesMut},
+        component::Component,
+        entity::Entity,
+        schedule::IntoSystemConfigs,
+        system::Query,
+        world::{Mut, World},
+    },
+    render::view::{RenderLayers, Visibility, VisibilitySystems},
+};
+
+/// Keeps pooled scratch entities living directly in the main world from rendering.
+///
+/// While present, the entity's [`Visibility`] is forced to [`Visibility::Hidden`] and its
+/// [`RenderLayers`] to [`RenderLayers::none`]. Values the generator assigns in the meantime are
+/// remembered and restored by [`strip_isolation`].
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct ScratchIsolation {
+    visibility: Option<Visibility>,
+    render_layers: Option<RenderLayers>,
+}
+
+/// Re-applies isolation every frame, see [`enforce_scratch_isolation`].
+///
+/// Only needed when pooled scratch entities live in the main world - scratch worlds aren't
+/// rendered.
+pub struct ScratchIsolationPlugin;
+
+impl Plugin for ScratchIsolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SlotsCommitted>().add_systems(
+            PostUpdate,
+            enforce_scratch_isolation.before(VisibilitySystems::VisibilityPropagate),
+        );
+    }
+}
+
+impl EntityPool {
+    /// Returns a handle to an entity from the pool, isolated from rendering with
+    /// [`ScratchIsolation`].
+    ///
+    /// # Panics
+    /// Panics on pool exhaustion
+    #[track_caller]
+    pub fn get_isolated(&mut self, world: &mut World) -> EntityHandle {
+        let Some(handle) = self.try_get_isolated(world) else {
+            self.exhausted("all entities in use");
+        };
+        handle
+    }
+
+    /// Like [`EntityPool::get_isolated`] but returns `None` on pool exhaustion.
+    #[track_caller]
+    pub fn try_get_isolated(&mut self, world: &mut World) -> Option<EntityHandle> {
+        self.debug_assert_world(world);
+
+        let handle = self.try_get_live(world)?;
+        isolate(world, handle.entity());
+        Some(handle)
+    }
+}
+
+/// Hides `entity` from rendering until [`strip_isolation`] is called. Already isolated entities
+/// are hidden again right away.
+///
+/// # Panics
+/// Panics if `entity` doesn't exist.
+pub fn isolate(world: &mut World, entity: Entity) {
+    let mut entity = world.entity_mut(entity);
+    let Some(mut isolation) = entity.get::<ScratchIsolation>().copied() else {
+        let isolation = ScratchIsolation {
+            visibility: entity.get::<Visibility>().copied(),
+            render_layers: entity.get::<RenderLayers>().copied(),
+        };
+        entity.insert((isolation, Visibility::Hidden, RenderLayers::none()));
+        return;
+    };
+
+    match entity.get_mut::<Visibility>() {
+        Some(visibility) => force(
+            visibility,
+            Visibility::Hidden,
+            &mut isolation.visibility,
+            true,
+        ),
+        None => {
+            entity.insert(Visibility::Hidden);
+        }
+    }
+    match entity.get_mut::<RenderLayers>() {
+        Some(render_layers) => force(
+            render_layers,
+            RenderLayers::none(),
+            &mut isolation.render_layers,
+            true,
+        ),
+        None => {
+            entity.insert(RenderLayers::none());
+        }
+    }
+    *entity
+        .get_mut::<ScratchIsolation>()
+        .expect("checked above")
+        .bypass_change_detection() = isolation;
+}
+
+/// Removes [`ScratchIsolation`] from `entity`, restoring the visibility and render layers the
+/// generator assigned, or removing them if it never did. Does nothing for entities that aren't
+/// isolated.
+///
+/// # Panics
+/// Panics if `entity` doesn't exist.
+pub fn strip_isolation(world: &mut World, entity: Entity) {
+    let mut entity = world.entity_mut(entity);
+    let Some(isolation) = entity.take::<ScratchIsolation>() else {
+        return;
+    };
+
+    match isolation.visibility {
+        Some(visibility) => entity.insert(visibility),
+        None => entity.remove::<Visibility>(),
+    };
+    match isolation.render_layers {
+        Some(render_layers) => entity.insert(render_layers),
+        None => entity.remove::<RenderLayers>(),
+    };
+}
+
+/// Forces isolated entities back to hidden after generators touched their visibility or render
+/// layers, remembering the assigned values for [`strip_isolation`].
+pub fn enforce_scratch_isolation(
+    mut isolated: Query<(
+        &mut ScratchIsolation,
+        Option<&mut Visibility>,
+        Option<&mut RenderLayers>,
+    )>,
+) {
+    for (mut isolation, visibility, render_layers) in &mut isolated {
+        // the isolating values themselves were just inserted, only others were assigned since
+        let just_isolated = isolation.is_added();
+        let isolation = isolation.bypass_change_detection();
+        if let Some(visibility) = visibility {
+            force(
+                visibility,
+                Visibility::Hidden,
+                &mut isolation.visibility,
+                just_isolated,
+            );
+        }
+        if let Some(render_layers) = render_layers {
+            force(
+                render_layers,
+                RenderLayers::none(),
+                &mut isolation.render_layers,
+                just_isolated,
+            );
+        }
+    }
+}
+
+/// Resets `value` to `forced`, remembering what the generator assigned. Forced values are written
+/// without triggering change detection, so a change to `forced` itself was made by the generator
+/// too - unless the isolating values were just inserted.
+fn force<T: Component + Copy + PartialEq>(
+    mut value: Mut<T>,
+    forced: T,
+    remembered: &mut Option<T>,
+    just_isolated: bool,
+) {
+    if *value != forced {
+        *remembered = Some(*value);
+        *value.bypass_change_detection() = forced;
+    } else if value.is_changed() && !just_isolated {
+        *remembered = Some(forced);
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+
+/// Identifies one checkout of a pooled slot.
+///
+/// The index addresses the slot, the generation is bumped every time the slot is reclaimed, so a
+/// key stays unique for as long as the pool lives (modulo `u32` wrap-around). Keys are plain data and
+/// validated on use - see [`EntityPool::resolve`](crate::EntityPool::resolve).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SlotKey {
+    index: u32,
+    generation: u32,
+}
+
+/// Raw, layout-stable representation of a [`SlotKey`] for FFI and scripting layers.
+///
+/// The layout is `#[repr(C)]` and will not change between versions. Raw slots carry no guarantees
+/// on their own - converting one back with [`SlotKey::from_raw`] always succeeds, and the resulting
+/// key is checked against the pool when used.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RawSlot {
+    pub index: u32,
+    pub generation: u32,
+}
+
+impl SlotKey {
+    pub(crate) fn new(index: u32, generation: u32) -> Self {
+        Self { index, generation }
+    }
+
+    /// Index of the slot in the pool.
+    pub fn index(self) -> u32 {
+        self.index
+    }
+
+    /// Number of times the slot was reclaimed before this checkout.
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+
+    /// Converts the key into its FFI representation.
+    pub fn to_raw(self) -> RawSlot {
+        RawSlot {
+            index: self.index,
+            generation: self.generation,
+        }
+    }
+
+    /// Rebuilds a key from its FFI representation.
+    pub fn from_raw(raw: RawSlot) -> Self {
+        Self::new(raw.index, raw.generation)
+    }
+
+    /// Packs the key into a `u64` - generation in the high 32 bits, index in the low 32 bits (the
+    /// same layout as [`Entity::to_bits`](bevy::ecs::entity::Entity::to_bits)). Useful for
+    /// scripting runtimes that only have numbers.
+    pub fn to_bits(self) -> u64 {
+        (self.generation as u64) << 32 | self.index as u64
+    }
+
+    /// Inverse of [`SlotKey::to_bits`].
+    pub fn from_bits(bits: u64) -> Self {
+        Self::new(bits as u32, (bits >> 32) as u32)
+    }
+}
+
+impl fmt::Debug for SlotKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+impl From<RawSlot> for SlotKey {
+    fn from(raw: RawSlot) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<SlotKey> for RawSlot {
+    fn from(key: SlotKey) -> Self {
+        key.to_raw()
+    }
+}
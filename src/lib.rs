@@ -1,12 +1,110 @@
 use bevy::ecs::{
-    entity::Entity,
-    world::{EntityWorldMut, World, WorldId},
+    entity::{Entity, EntityHashSet},
+    query::With,
+    system::Resource,
+    world::{World, WorldId},
 };
-use std::{ops::Deref, sync::Arc};
+use bevy::log::warn;
+use std::{panic::Location, sync::Arc};
+
+mod access;
+mod age;
+mod allocator;
+pub mod bake_runner;
+mod block;
+mod commit;
+mod concurrency;
+mod coordinator;
+mod dedup;
+mod diagnostics;
+mod event_log;
+mod fence;
+mod guard;
+mod handle;
+mod isolation;
+mod key;
+#[cfg(feature = "profiling")]
+mod profiling;
+mod progress;
+mod quarantine;
+mod registry;
+mod replay;
+mod reserve;
+mod reset;
+mod rng;
+mod schedule;
+pub mod scripting;
+mod shared;
+mod slot_map;
+mod stats;
+mod task;
+mod template;
+mod validate;
+mod watchdog;
+mod watermark;
+
+pub use age::{GenerationStats, PoolGeneration, GENERATION_HISTORY};
+pub use allocator::{
+    BitmapAllocator, GroupedAllocator, RingAllocator, SlotAllocator, StackAllocator,
+};
+pub use block::BlockHandle;
+pub use commit::SlotsCommitted;
+pub use concurrency::TaskConcurrency;
+pub use coordinator::{sync_pool_coordinator, CoordinatorAudit, MirrorId, PoolCoordinator};
+pub use dedup::{CopyBackDedup, DuplicateSlot};
+pub use diagnostics::{EntityPoolDiagnosticsPlugin, LeakReport, PoolDiagnosticPaths};
+pub use event_log::{PoolLogEntry, PoolOp};
+pub use fence::{Fence, PooledTaskFences};
+pub use guard::{guard_entity_pool, PoolEntitiesLost, PoolGuardPlugin, PoolRecovery};
+pub use handle::{EntityHandle, Reclaim};
+pub use isolation::{
+    enforce_scratch_isolation, isolate, strip_isolation, ScratchIsolation, ScratchIsolationPlugin,
+};
+pub use key::{RawSlot, SlotKey};
+#[cfg(feature = "profiling")]
+pub use profiling::{
+    AcquisitionContext, AcquisitionGrouping, AcquisitionScope, AcquisitionSummary, ProfiledPool,
+    MAX_ACQUISITION_CONTEXTS,
+};
+pub use progress::ProgressReporter;
+pub use quarantine::{
+    apply_quarantine, quarantine, release_quarantine, Quarantine, QuarantinePlugin, QuarantineSet,
+    QuarantineSystems, Quarantined,
+};
+pub use registry::{PoolId, PoolRegistry, RangeOverlap};
+pub use replay::{PendingRecording, Replay, ScratchRecording, ScratchSteps};
+pub use reserve::LiveAcquireError;
+pub use rng::ScratchRng;
+pub use schedule::{ScheduleRun, ScratchSchedule, StepControl, SystemStep};
+pub use shared::{SharedEntityHandle, SharedEntityPool};
+pub use slot_map::PooledSlotMap;
+pub use stats::{PoolStats, DEFAULT_STATS_SMOOTHING};
+pub use task::{
+    maintain_entity_pool, poll_pooled_tasks, spawn_pooled_task, PooledTaskFinished, PooledTaskId,
+    PooledTaskPlugin, PooledTasks, ScratchContext,
+};
+pub use template::{PoolTemplatePlugin, SlotTemplate};
+pub use validate::{PooledTaskRejected, ResultValidators, ValidationFailure, ValidationReport};
+pub use watchdog::{
+    watch_pooled_tasks, PooledTaskStalled, PooledTaskWatchdog, PooledTaskWatchdogPlugin,
+};
+pub use watermark::{PoolWatermarkPlugin, WatermarkCrossed};
+
+use age::GenerationTracker;
+use event_log::EventLog;
+use handle::{lock_releases, ReleaseQueue};
+#[cfg(feature = "profiling")]
+use profiling::Profiler;
+use registry::Registration;
+use reserve::PendingReservations;
+use stats::StatsTracker;
+use watermark::Watermarks;
 
 /// Fixed capacity entity pool - gives out temporary access to a fixed number of entities via handles.
-/// Handles can only be reclaimed by calling '[EntityPool::free]' - it is expected that only
-/// locally relevant entities are used in the scratch-world and that entities are periodically freed.
+/// Handles are RAII guards: dropping one (or inserting [`Reclaim`] on its entity) queues the entity for
+/// reclamation, and [`EntityPool::reclaim`] clears queued entities and returns them to the free list.
+/// It is expected that only locally relevant entities are used in the scratch-world and that entities
+/// are periodically freed - [`EntityPool::set_max_generation_age`] turns that into a check.
 ///
 /// Primitive that enables using ECS Worlds as procedural scratch space in async tasks. Intended
 /// for use in long-running single-threaded contexts with exclusive world access.
@@ -20,64 +118,500 @@ use std::{ops::Deref, sync::Arc};
 /// - perform hard work and store the results of said work in the scratch world
 /// - copy that work back into main world using bevy_scene
 ///
+/// A pool belongs to the world it was created with. Processes with several primary worlds (e.g. a
+/// server and a client world) create one pool per world - plugins, registries and diagnostics all
+/// work on the resources of the world they run in, see [`EntityPool::belongs_to`].
+///
 /// # Panics
 /// Panics on pool exhaustion.
+#[derive(Resource)]
 pub struct EntityPool {
+    id: PoolId,
     world_id: WorldId,
     entities: Arc<[Entity]>,
-    free_cursor: usize,
-    handles: Vec<EntityHandle>,
+    /// Bumped every time a slot is reclaimed so stale handles can be told apart from live ones.
+    generations: Vec<u32>,
+    in_use: Vec<bool>,
+    /// Slots published read-only for later pipeline stages, cleared on release.
+    published: Vec<bool>,
+    allocator: Box<dyn SlotAllocator>,
+    releases: ReleaseQueue,
+    stats: StatsTracker,
+    /// Acquisition sites of in use slots, only tracked while leak detection is enabled.
+    acquired_at: Option<Vec<Option<&'static Location<'static>>>>,
+    leaks: Vec<LeakReport>,
+    pending: PendingReservations,
+    watermarks: Watermarks,
+    /// Component layout slots are reset to on release.
+    template: Option<SlotTemplate>,
+    task_concurrency: Option<TaskConcurrency>,
+    event_log: Option<EventLog>,
+    /// Pool generations, see [`EntityPool::next_generation`].
+    ages: GenerationTracker,
+    /// Slots cleared since the last [`PoolCoordinator`] sync, only tracked while it has mirrors.
+    freed: Option<Vec<u32>>,
+    /// Slots whose lost entity's index was taken by another entity, see [`EntityPool::recover`].
+    /// They count as neither free nor in use.
+    conflicted: Vec<u32>,
+    /// Releases the pool's entities in the world's [`PoolRegistry`] once the pool is dropped.
+    registration: Option<Registration>,
+    /// Acquisition contexts, only captured with the `profiling` feature.
+    #[cfg(feature = "profiling")]
+    profiler: Profiler,
 }
 
 impl EntityPool {
     /// Initializes an entity pool and reserves required entities.
     ///
     /// # Panics
-    /// Panics if it isn't possible to spawn all entities.
+    /// Panics if it isn't possible to spawn all entities, or if the world has a [`PoolRegistry`]
+    /// and the entities overlap another pool's.
     pub fn new(entities: Vec<Entity>, world: &mut World) -> Self {
-        world
-            .insert_or_spawn_batch(entities.iter().copied().map(|e| (e, ())))
-            .inspect_err(|e| panic!("Failed to spawn all entities {e:?}"));
+        Self::with_allocator(entities, world, StackAllocator::default())
+    }
+
+    /// Like [`EntityPool::new`], delegating slot selection to `allocator` instead of the default
+    /// [`StackAllocator`].
+    ///
+    /// # Panics
+    /// Panics if it isn't possible to spawn all entities, or if the world has a [`PoolRegistry`]
+    /// and the entities overlap another pool's.
+    pub fn with_allocator(
+        entities: Vec<Entity>,
+        world: &mut World,
+        mut allocator: impl SlotAllocator,
+    ) -> Self {
+        let id = PoolId::next();
+        let mut registration = None;
+        register_entities(id, &entities, world, &mut registration);
+
+        if let Err(e) = world.insert_or_spawn_batch(entities.iter().copied().map(|e| (e, ()))) {
+            panic!("Failed to spawn all entities {e:?}");
+        }
 
+        let capacity = entities.len();
+        allocator.grow(0..capacity as u32);
         Self {
+            id,
             world_id: world.id(),
             entities: Arc::from(entities.as_slice()),
-            free_cursor: 0,
-            handles: Vec::new(),
+            generations: vec![0; capacity],
+            in_use: vec![false; capacity],
+            published: vec![false; capacity],
+            allocator: Box::new(allocator),
+            releases: ReleaseQueue::default(),
+            stats: StatsTracker::default(),
+            acquired_at: None,
+            leaks: Vec::new(),
+            pending: PendingReservations::default(),
+            watermarks: Watermarks::default(),
+            template: None,
+            task_concurrency: None,
+            event_log: None,
+            ages: GenerationTracker::with_capacity(capacity),
+            freed: None,
+            conflicted: Vec::new(),
+            registration,
+            #[cfg(feature = "profiling")]
+            profiler: Profiler::with_capacity(capacity),
         }
     }
 
-    /// Returns an entity from the pool.
+    /// Grows the pool by reserving additional entities.
     ///
     /// # Panics
-    /// Panics on pool exhaustion
-    pub fn get(&mut self) -> Entity {
-        if self.free_cursor > self.entities.len() - 1 {
-            panic!("pool exhaustion - all entities in use");
+    /// Panics if it isn't possible to spawn all entities, or if the world has a [`PoolRegistry`]
+    /// and the entities overlap another pool's.
+    pub fn grow(&mut self, entities: Vec<Entity>, world: &mut World) {
+        self.debug_assert_world(world);
+        register_entities(self.id, &entities, world, &mut self.registration);
+
+        if let Err(e) = world.insert_or_spawn_batch(entities.iter().copied().map(|e| (e, ()))) {
+            panic!("Failed to spawn all entities {e:?}");
+        }
+
+        let start = self.entities.len() as u32;
+        let end = start + entities.len() as u32;
+        self.entities = self.entities.iter().copied().chain(entities).collect();
+        self.generations.resize(end as usize, 0);
+        self.in_use.resize(end as usize, false);
+        self.published.resize(end as usize, false);
+        self.ages.resize(end as usize);
+        #[cfg(feature = "profiling")]
+        self.profiler.resize(end as usize);
+        if let Some(acquired_at) = &mut self.acquired_at {
+            acquired_at.resize(end as usize, None);
         }
+        self.allocator.grow(start..end);
+        for slot in start..end {
+            self.apply_template(slot, world);
+        }
+        self.log(PoolOp::Grow {
+            added: (end - start) as usize,
+        });
+        self.update_watermarks();
+    }
+
+    /// Identifies this pool in a [`PoolRegistry`].
+    pub fn id(&self) -> PoolId {
+        self.id
+    }
+
+    /// World the pool reserved its entities in.
+    pub fn world_id(&self) -> WorldId {
+        self.world_id
+    }
+
+    /// Returns `true` if the pool's entities live in `world` - with several primary worlds (e.g. a
+    /// server and a client world in one process) every world has its own pools, and a pool must
+    /// only ever be used with its own world.
+    pub fn belongs_to(&self, world: &World) -> bool {
+        self.world_id == world.id()
+    }
+
+    /// Returns `true` if `handle` was checked out of this pool. Keys and entities of pools in
+    /// different worlds can be identical, handles can still be told apart.
+    pub fn owns(&self, handle: &EntityHandle) -> bool {
+        handle
+            .releases
+            .as_ref()
+            .is_some_and(|releases| Arc::ptr_eq(&self.releases, releases))
+    }
+
+    /// Total number of reserved entities.
+    pub fn capacity(&self) -> usize {
+        self.entities.len()
+    }
 
-        let handle = EntityHandle {
-            entity: self.entities[self.free_cursor],
-            dropped: false,
+    /// All reserved entities, indexed by slot.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Returns the entity reserved for `slot`.
+    ///
+    /// # Panics
+    /// Panics if `slot` is out of range.
+    pub fn entity(&self, slot: u32) -> Entity {
+        self.entities[slot as usize]
+    }
+
+    /// Returns a handle to an entity from the pool.
+    ///
+    /// # Panics
+    /// Panics on pool exhaustion
+    #[track_caller]
+    pub fn get(&mut self) -> EntityHandle {
+        let Some(handle) = self.try_get() else {
+            self.exhausted("all entities in use");
+        };
+        handle
+    }
+
+    /// Returns a handle to an entity from the pool, or `None` on pool exhaustion.
+    #[track_caller]
+    pub fn try_get(&mut self) -> Option<EntityHandle> {
+        let Some(slot) = self.allocator.allocate() else {
+            self.record_failure();
+            return None;
         };
-        self.handles.push(handle);
-        self.free_cursor += 1;
+        Some(self.checkout(slot))
+    }
+
+    /// Yields handles until the pool runs dry, for "do as much as capacity allows" loops. Running
+    /// out ends the iterator without counting as a failed acquisition.
+    ///
+    /// ```ignore
+    /// for (prop, handle) in props.iter().zip(pool.acquire_while_available()) {
+    ///     // ...
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn acquire_while_available(&mut self) -> AcquireWhileAvailable<'_> {
+        AcquireWhileAvailable {
+            pool: self,
+            caller: Location::caller(),
+        }
+    }
+
+    /// Clears and returns to the free list every entity whose handle was dropped or that had
+    /// [`Reclaim`] inserted since the last call.
+    ///
+    /// Bevy 0.13 has no observers to catch [`Reclaim`] insertions, so marked entities are looked up
+    /// with a `With<Reclaim>` query on every call. The pool's slots are only scanned if some entity
+    /// is marked.
+    pub fn reclaim(&mut self, world: &mut World) {
+        self.debug_assert_world(world);
+
+        let released = std::mem::take(&mut *lock_releases(&self.releases));
+        for key in released {
+            // handles invalidated by an earlier reclaim or `free_entities` are ignored
+            if self.is_valid(key) {
+                self.release_slot(key.index(), world);
+            }
+        }
+
+        let marked: EntityHashSet = world
+            .query_filtered::<Entity, With<Reclaim>>()
+            .iter(world)
+            .collect();
+        if marked.is_empty() {
+            return;
+        }
+        for slot in 0..self.entities.len() as u32 {
+            if self.in_use[slot as usize] && marked.contains(&self.entities[slot as usize]) {
+                self.release_slot(slot, world);
+            }
+        }
+    }
+
+    /// Returns `true` if `key` refers to the current checkout of an in use slot.
+    pub fn is_valid(&self, key: SlotKey) -> bool {
+        let index = key.index() as usize;
+        index < self.entities.len()
+            && self.in_use[index]
+            && self.generations[index] == key.generation()
+    }
+
+    /// Returns the entity behind `key`, or `None` if the key is stale or out of range.
+    ///
+    /// Keys rebuilt from raw data (see [`SlotKey::from_raw`]) should always go through this.
+    pub fn resolve(&self, key: SlotKey) -> Option<Entity> {
+        self.is_valid(key)
+            .then(|| self.entities[key.index() as usize])
+    }
+
+    /// Catches pools used with another world's entities, which otherwise corrupts slots silently.
+    #[track_caller]
+    pub(crate) fn debug_assert_world(&self, world: &World) {
+        debug_assert!(
+            self.belongs_to(world),
+            "{:?} belongs to {:?}, not {:?}",
+            self.id,
+            self.world_id,
+            world.id()
+        );
+    }
+
+    /// Immediately reclaims the slot behind `key`, invalidating its handle. Returns `false` (and
+    /// does nothing) if the key is stale or out of range.
+    pub fn release(&mut self, key: SlotKey, world: &mut World) -> bool {
+        self.debug_assert_world(world);
+
+        let valid = self.is_valid(key);
+        if valid {
+            self.release_slot(key.index(), world);
+        }
+        valid
+    }
+
+    /// Immediately reclaims the slot behind `handle` without leaving its release queued. Handles of
+    /// other pools are just dropped.
+    pub(crate) fn release_handle(&mut self, handle: EntityHandle, world: &mut World) {
+        if self.owns(&handle) {
+            self.release(handle.disarm(), world);
+        }
+    }
 
-        self.handles.last().unwrap()
+    /// Invalidates the handle to `slot` and reclaims its entity. Free slots are cleared (and reset to
+    /// the template) again, conflicted slots are left to [`EntityPool::recover`].
+    ///
+    /// # Panics
+    /// Panics if `slot` is out of range.
+    pub fn free_slot(&mut self, slot: u32, world: &mut World) {
+        self.debug_assert_world(world);
+
+        if self.conflicted.contains(&slot) {
+            // the entity is gone, the slot returns through `recover`
+        } else if self.in_use[slot as usize] {
+            self.release_slot(slot, world);
+        } else {
+            world
+                .entity_mut(self.entities[slot as usize])
+                .retain::<()>();
+            self.apply_template(slot, world);
+            self.record_freed(slot);
+        }
     }
 
-    /// Invalidates and reclaims all in use entities.  
+    /// Invalidates and reclaims all in use entities.
+    ///
+    /// With leak detection enabled, entities whose handles are still alive are recorded as leaks.
     pub fn free_entities(&mut self, world: &mut World) {
-        // make sure world we're freeing from is the same world we initialized with
-        debug_assert_eq!(self.world_id, world.id());
+        self.debug_assert_world(world);
+
+        if self.acquired_at.is_some() {
+            self.record_leaks();
+        }
 
-        for entity in self.entities[0..self.free_cursor].iter().copied() {
-            // TODO: 0.14 clear all components on entity
-            // world.entity_mut(entity).clear();
+        for slot in 0..self.entities.len() as u32 {
+            if self.in_use[slot as usize] && !self.conflicted.contains(&slot) {
+                self.release_slot(slot, world);
+            }
         }
+        // everything queued so far refers to generations we just invalidated
+        lock_releases(&self.releases).clear();
+    }
 
-        for handle in &mut self.handles {
-            handle.dropped = true;
+    /// Returns current usage along with the smoothed occupancy and acquisition rate.
+    pub fn stats(&self) -> PoolStats {
+        self.stats
+            .snapshot(self.in_use_count(), self.entities.len())
+    }
+
+    /// Folds the usage observed since the previous call into the smoothed statistics.
+    ///
+    /// Call this once per frame (or at whatever cadence the smoothed values should be measured in).
+    pub fn sample_stats(&mut self) {
+        self.stats.sample(self.in_use_count(), self.entities.len());
+    }
+
+    /// Sets the weight given to the newest sample by [`EntityPool::sample_stats`], defaults to
+    /// [`DEFAULT_STATS_SMOOTHING`]. Higher values react faster, lower values are more stable.
+    ///
+    /// # Panics
+    /// Panics if `smoothing` is not in `(0, 1]`.
+    pub fn set_stats_smoothing(&mut self, smoothing: f32) {
+        self.stats.set_smoothing(smoothing);
+    }
+
+    /// Enables or disables recording the acquisition site of every handed out entity, so entities
+    /// whose handles were never dropped by the time [`EntityPool::free_entities`] runs can be
+    /// reported. Disabling discards outstanding sites but keeps recorded leaks.
+    pub fn set_leak_detection(&mut self, enabled: bool) {
+        self.acquired_at = enabled.then(|| vec![None; self.entities.len()]);
+    }
+
+    /// Leaks recorded by [`EntityPool::free_entities`] while leak detection was enabled.
+    pub fn leaks(&self) -> &[LeakReport] {
+        &self.leaks
+    }
+
+    /// Returns and clears the recorded leaks.
+    pub fn take_leaks(&mut self) -> Vec<LeakReport> {
+        std::mem::take(&mut self.leaks)
+    }
+
+    fn record_leaks(&mut self) {
+        let released = lock_releases(&self.releases).clone();
+        let Some(acquired_at) = &self.acquired_at else {
+            return;
+        };
+
+        for slot in 0..self.entities.len() as u32 {
+            let key = SlotKey::new(slot, self.generations[slot as usize]);
+            // sites are only missing for slots acquired before leak detection was enabled
+            let (true, Some(site)) = (self.in_use[slot as usize], acquired_at[slot as usize])
+            else {
+                continue;
+            };
+            if released.contains(&key) {
+                continue;
+            }
+
+            let entity = self.entities[slot as usize];
+            warn!("pooled entity {entity:?} ({key:?}) acquired at {site} was never released");
+            self.leaks.push(LeakReport {
+                key,
+                entity,
+                acquired_at: site,
+            });
+        }
+    }
+
+    fn update_watermarks(&mut self) {
+        let (in_use, capacity) = (self.in_use_count(), self.entities.len());
+        self.watermarks.update(self.id, in_use, capacity);
+    }
+
+    fn in_use_count(&self) -> usize {
+        self.entities.len() - self.allocator.available() - self.conflicted.len()
+    }
+
+    /// Marks a slot already removed from the free list as in use and hands out its handle.
+    #[track_caller]
+    fn checkout(&mut self, slot: u32) -> EntityHandle {
+        self.checkout_at(slot, Location::caller())
+    }
+
+    fn checkout_at(&mut self, slot: u32, caller: &'static Location<'static>) -> EntityHandle {
+        self.in_use[slot as usize] = true;
+        self.stats.record_acquire(self.in_use_count());
+        self.ages.record_acquire(slot, self.in_use_count());
+        if let Some(acquired_at) = &mut self.acquired_at {
+            acquired_at[slot as usize] = Some(caller);
+        }
+        #[cfg(feature = "profiling")]
+        self.profiler.record(slot, caller);
+        self.log(PoolOp::Acquire { slot });
+        self.update_watermarks();
+
+        EntityHandle {
+            entity: self.entities[slot as usize],
+            key: SlotKey::new(slot, self.generations[slot as usize]),
+            releases: Some(self.releases.clone()),
+        }
+    }
+
+    fn release_slot(&mut self, slot: u32, world: &mut World) {
+        let index = slot as usize;
+        // 0.13 has no `EntityWorldMut::clear` - retaining the empty bundle removes everything
+        world.entity_mut(self.entities[index]).retain::<()>();
+        self.apply_template(slot, world);
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.in_use[index] = false;
+        self.published[index] = false;
+        self.ages.record_release(slot);
+        #[cfg(feature = "profiling")]
+        self.profiler.release(slot);
+        if let Some(acquired_at) = &mut self.acquired_at {
+            acquired_at[index] = None;
+        }
+        self.allocator.free(slot);
+        self.record_freed(slot);
+        self.log(PoolOp::Free { slot });
+        self.update_watermarks();
+    }
+}
+
+/// Iterator returned by [`EntityPool::acquire_while_available`].
+pub struct AcquireWhileAvailable<'a> {
+    pool: &'a mut EntityPool,
+    caller: &'static Location<'static>,
+}
+
+impl Iterator for AcquireWhileAvailable<'_> {
+    type Item = EntityHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(slot) = self.pool.allocator.allocate() else {
+            self.pool.warn_pending_reservations();
+            return None;
+        };
+        Some(self.pool.checkout_at(slot, self.caller))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let available = self.pool.allocator.available();
+        (available, Some(available))
+    }
+}
+
+impl ExactSizeIterator for AcquireWhileAvailable<'_> {}
+
+fn register_entities(
+    pool: PoolId,
+    entities: &[Entity],
+    world: &mut World,
+    registration: &mut Option<Registration>,
+) {
+    if let Some(mut registry) = world.get_resource_mut::<PoolRegistry>() {
+        if let Err(e) = registry.register(pool, entities) {
+            panic!("Failed to register pool entities: {e}");
+        }
+        // the pool may have registered with a registry that was replaced since
+        if !registration.as_ref().is_some_and(|r| r.of(&registry)) {
+            *registration = Some(registry.registration(pool));
         }
     }
 }
@@ -0,0 +1,297 @@
+use crate::{EntityHandle, EntityPool, SlotKey};
+use bevy::{
+    core::FrameCount,
+    ecs::{
+        entity::Entity,
+        system::{Res, ResMut, SystemName, SystemParam},
+    },
+    utils::{HashMap, HashSet},
+};
+use std::{
+    borrow::Cow,
+    ops::{Deref, DerefMut},
+    panic::Location,
+    sync::Arc,
+};
+
+/// Distinct contexts [`EntityPool::acquisitions_by`] keeps totals for - acquisitions in contexts
+/// past it are counted under `"<other>"`, so per-chunk reasons can't grow the profiler unbounded.
+pub const MAX_ACQUISITION_CONTEXTS: usize = 1024;
+
+/// Who acquired a slot, when and why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AcquisitionContext {
+    pub system: Option<Arc<str>>,
+    pub schedule: Option<Arc<str>>,
+    pub frame: Option<u32>,
+    pub reason: Option<Arc<str>>,
+    pub location: &'static Location<'static>,
+}
+
+/// Attached to every acquisition until replaced, see [`EntityPool::set_acquisition_scope`].
+///
+/// bevy doesn't expose the running schedule to systems - set `schedule` yourself where it matters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AcquisitionScope {
+    pub system: Option<Cow<'static, str>>,
+    pub schedule: Option<Cow<'static, str>>,
+    pub frame: Option<u32>,
+    pub reason: Option<Cow<'static, str>>,
+}
+
+/// Field [`EntityPool::acquisitions_by`] groups acquisitions by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AcquisitionGrouping {
+    System,
+    Schedule,
+    Reason,
+    Location,
+}
+
+/// Acquisitions of one group.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AcquisitionSummary {
+    /// Slots acquired by the group that are still in use.
+    pub live: usize,
+    /// Every acquisition by the group since the pool was created.
+    pub total: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct ContextKey {
+    system: Option<Arc<str>>,
+    schedule: Option<Arc<str>>,
+    reason: Option<Arc<str>>,
+    location: &'static Location<'static>,
+}
+
+impl ContextKey {
+    fn group(&self, grouping: AcquisitionGrouping) -> String {
+        group_name(
+            grouping,
+            self.system.as_deref(),
+            self.schedule.as_deref(),
+            self.reason.as_deref(),
+            self.location,
+        )
+    }
+}
+
+/// Interned strings and frame attached to acquisitions.
+#[derive(Clone, Default)]
+struct Attribution {
+    system: Option<Arc<str>>,
+    schedule: Option<Arc<str>>,
+    frame: Option<u32>,
+    reason: Option<Arc<str>>,
+}
+
+#[derive(Default)]
+pub(crate) struct Profiler {
+    scope: AcquisitionScope,
+    /// `scope` with its strings interned.
+    attribution: Attribution,
+    /// Replaces `attribution` for the next acquisition, see [`ProfiledPool`].
+    next: Option<Attribution>,
+    /// Context of every in use slot.
+    live: Vec<Option<AcquisitionContext>>,
+    /// At most [`MAX_ACQUISITION_CONTEXTS`] entries.
+    totals: HashMap<ContextKey, u64>,
+    /// Acquisitions in contexts that didn't fit into `totals`.
+    overflow: u64,
+    /// At most [`MAX_ACQUISITION_CONTEXTS`] names, later ones aren't shared.
+    names: HashSet<Arc<str>>,
+}
+
+impl Profiler {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            live: vec![None; capacity],
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn resize(&mut self, capacity: usize) {
+        self.live.resize(capacity, None);
+    }
+
+    pub(crate) fn record(&mut self, slot: u32, location: &'static Location<'static>) {
+        let attribution = self.next.take().unwrap_or_else(|| self.attribution.clone());
+        let key = ContextKey {
+            system: attribution.system.clone(),
+            schedule: attribution.schedule.clone(),
+            reason: attribution.reason.clone(),
+            location,
+        };
+        let full = self.totals.len() >= MAX_ACQUISITION_CONTEXTS;
+        match self.totals.get_mut(&key) {
+            Some(total) => *total += 1,
+            None if full => self.overflow += 1,
+            None => {
+                self.totals.insert(key, 1);
+            }
+        }
+
+        self.live[slot as usize] = Some(AcquisitionContext {
+            system: attribution.system,
+            schedule: attribution.schedule,
+            frame: attribution.frame,
+            reason: attribution.reason,
+            location,
+        });
+    }
+
+    pub(crate) fn release(&mut self, slot: u32) {
+        self.live[slot as usize] = None;
+    }
+
+    fn set_scope(&mut self, scope: AcquisitionScope) -> AcquisitionScope {
+        self.attribution = Attribution {
+            system: scope.system.as_deref().map(|name| self.intern(name)),
+            schedule: scope.schedule.as_deref().map(|name| self.intern(name)),
+            frame: scope.frame,
+            reason: scope.reason.as_deref().map(|name| self.intern(name)),
+        };
+        std::mem::replace(&mut self.scope, scope)
+    }
+
+    /// Shares one allocation between every acquisition attributed to `name`.
+    fn intern(&mut self, name: &str) -> Arc<str> {
+        if let Some(name) = self.names.get(name) {
+            return name.clone();
+        }
+        let name: Arc<str> = Arc::from(name);
+        if self.names.len() < MAX_ACQUISITION_CONTEXTS {
+            self.names.insert(name.clone());
+        }
+        name
+    }
+}
+
+impl EntityPool {
+    /// Attaches `scope` to acquisitions from now on, returning the previous scope.
+    pub fn set_acquisition_scope(&mut self, scope: AcquisitionScope) -> AcquisitionScope {
+        self.profiler.set_scope(scope)
+    }
+
+    pub fn acquisition_scope(&self) -> &AcquisitionScope {
+        &self.profiler.scope
+    }
+
+    /// Returns the context `key` was acquired in, `None` if the key is stale.
+    pub fn acquisition_context(&self, key: SlotKey) -> Option<&AcquisitionContext> {
+        if !self.is_valid(key) {
+            return None;
+        }
+        self.profiler.live[key.index() as usize].as_ref()
+    }
+
+    /// Contexts of every in use slot.
+    pub fn live_acquisitions(&self) -> impl Iterator<Item = (Entity, &AcquisitionContext)> + '_ {
+        self.profiler
+            .live
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, context)| Some((self.entities[slot], context.as_ref()?)))
+    }
+
+    /// Live and total acquisitions grouped by `grouping`, groups holding the most slots first.
+    /// Totals of contexts past [`MAX_ACQUISITION_CONTEXTS`] are grouped under `"<other>"`.
+    pub fn acquisitions_by(
+        &self,
+        grouping: AcquisitionGrouping,
+    ) -> Vec<(String, AcquisitionSummary)> {
+        let mut groups: HashMap<String, AcquisitionSummary> = HashMap::default();
+        for (key, &total) in &self.profiler.totals {
+            groups.entry(key.group(grouping)).or_default().total += total;
+        }
+        if self.profiler.overflow > 0 {
+            groups.entry("<other>".to_owned()).or_default().total += self.profiler.overflow;
+        }
+        for (_, context) in self.live_acquisitions() {
+            let group = group_name(
+                grouping,
+                context.system.as_deref(),
+                context.schedule.as_deref(),
+                context.reason.as_deref(),
+                context.location,
+            );
+            groups.entry(group).or_default().live += 1;
+        }
+
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.sort_by(|(a_name, a), (b_name, b)| {
+            (b.live, b.total, a_name).cmp(&(a.live, a.total, b_name))
+        });
+        groups
+    }
+}
+
+fn group_name(
+    grouping: AcquisitionGrouping,
+    system: Option<&str>,
+    schedule: Option<&str>,
+    reason: Option<&str>,
+    location: &'static Location<'static>,
+) -> String {
+    let name = match grouping {
+        AcquisitionGrouping::System => system,
+        AcquisitionGrouping::Schedule => schedule,
+        AcquisitionGrouping::Reason => reason,
+        AcquisitionGrouping::Location => return location.to_string(),
+    };
+    name.unwrap_or("<unknown>").to_owned()
+}
+
+/// [`EntityPool`] resource access that captures the calling system's name and the frame number
+/// with every acquisition made through it.
+#[derive(SystemParam)]
+pub struct ProfiledPool<'w, 's> {
+    pool: ResMut<'w, EntityPool>,
+    system: SystemName<'s>,
+    frame: Option<Res<'w, FrameCount>>,
+}
+
+impl ProfiledPool<'_, '_> {
+    /// Checks out an entity, recording `reason` with it.
+    ///
+    /// # Panics
+    /// Panics on pool exhaustion.
+    #[track_caller]
+    pub fn get(&mut self, reason: &str) -> EntityHandle {
+        match self.try_get(reason) {
+            Some(handle) => handle,
+            None => self.pool.exhausted("all entities in use"),
+        }
+    }
+
+    /// Checks out an entity, recording `reason` with it. Returns `None` on pool exhaustion.
+    #[track_caller]
+    pub fn try_get(&mut self, reason: &str) -> Option<EntityHandle> {
+        let profiler = &mut self.pool.profiler;
+        profiler.next = Some(Attribution {
+            system: Some(profiler.intern(self.system.name())),
+            schedule: profiler.attribution.schedule.clone(),
+            frame: self.frame.as_ref().map(|frame| frame.0),
+            reason: Some(profiler.intern(reason)),
+        });
+
+        let handle = self.pool.try_get();
+        self.pool.profiler.next = None;
+        handle
+    }
+}
+
+impl Deref for ProfiledPool<'_, '_> {
+    type Target = EntityPool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pool
+    }
+}
+
+impl DerefMut for ProfiledPool<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.pool
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    Arc,
+};
+
+#[derive(Debug, Default)]
+struct ProgressState {
+    beats: AtomicU64,
+    /// `f32` bits.
+    fraction: AtomicU32,
+    cancelled: AtomicBool,
+}
+
+/// Lets a pooled task report that it's still making progress, see
+/// [`PooledTaskWatchdogPlugin`](crate::PooledTaskWatchdogPlugin). Clones share the same state.
+#[derive(Clone, Debug, Default)]
+pub struct ProgressReporter(Arc<ProgressState>);
+
+impl ProgressReporter {
+    /// Signals liveness without a measurable fraction - call it from inner loops.
+    pub fn heartbeat(&self) {
+        self.0.beats.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Reports `fraction` of the work as done, clamped to `0.0..=1.0`. Counts as a heartbeat.
+    pub fn report(&self, fraction: f32) {
+        self.0
+            .fraction
+            .store(fraction.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+        self.heartbeat();
+    }
+
+    /// Last reported fraction.
+    pub fn progress(&self) -> f32 {
+        f32::from_bits(self.0.fraction.load(Ordering::Relaxed))
+    }
+
+    /// Number of heartbeats so far.
+    pub fn beats(&self) -> u64 {
+        self.0.beats.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` once the task was cancelled - its results are discarded, so bail out early.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{EntityHandle, EntityPool, SlotsCommitted};
+use bevy::{
+    app::{App, Plugin, PostUpdate},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::With,
+        schedule::{IntoSystemConfigs, SystemSet},
+        system::Resource,
+        world::{EntityWorldMut, World},
+    },
+};
+use std::any::TypeId;
+
+/// Marks a pooled scratch entity as uncommitted - components in the app's [`QuarantineSet`] are
+/// kept inert on it as [`Quarantined`] until [`release_quarantine`] is called.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Quarantine;
+
+/// A quarantined component, moved out of `T` so systems querying `T` (e.g. physics) don't see it.
+#[derive(Component, Debug)]
+pub struct Quarantined<T: Component>(pub T);
+
+/// Component types that must stay inert on uncommitted scratch entities, such as colliders and
+/// rigid bodies that would otherwise affect live physics.
+#[derive(Resource, Clone, Default)]
+pub struct QuarantineSet {
+    entries: Vec<QuarantineEntry>,
+}
+
+#[derive(Clone, Copy)]
+struct QuarantineEntry {
+    type_id: TypeId,
+    quarantine: fn(&mut EntityWorldMut),
+    release: fn(&mut EntityWorldMut),
+}
+
+impl QuarantineSet {
+    /// Adds `T` to the set.
+    pub fn with<T: Component>(mut self) -> Self {
+        self.add::<T>();
+        self
+    }
+
+    /// Adds `T` to the set. Adding a type twice has no effect.
+    pub fn add<T: Component>(&mut self) -> &mut Self {
+        if !self.contains::<T>() {
+            self.entries.push(QuarantineEntry {
+                type_id: TypeId::of::<T>(),
+                quarantine: quarantine_component::<T>,
+                release: release_component::<T>,
+            });
+        }
+        self
+    }
+
+    /// Returns `true` if `T` is in the set.
+    pub fn contains<T: Component>(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.type_id == TypeId::of::<T>())
+    }
+
+    fn quarantine(&self, entity: &mut EntityWorldMut) {
+        for entry in &self.entries {
+            (entry.quarantine)(entity);
+        }
+    }
+
+    fn release(&self, entity: &mut EntityWorldMut) {
+        for entry in &self.entries {
+            (entry.release)(entity);
+        }
+    }
+}
+
+/// Label for [`apply_quarantine`], order physics systems after it.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QuarantineSystems;
+
+/// Keeps the components of a [`QuarantineSet`] inert on every entity marked with [`Quarantine`].
+///
+/// Components are quarantined immediately by [`quarantine`] and again in [`PostUpdate`] (in
+/// [`QuarantineSystems`]) to catch components generators added during the frame.
+pub struct QuarantinePlugin {
+    pub set: QuarantineSet,
+}
+
+impl Plugin for QuarantinePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.set.clone())
+            .add_event::<SlotsCommitted>()
+            .add_systems(PostUpdate, apply_quarantine.in_set(QuarantineSystems));
+    }
+}
+
+impl EntityPool {
+    /// Returns a handle to an entity from the pool, marked with [`Quarantine`].
+    ///
+    /// # Panics
+    /// Panics on pool exhaustion
+    #[track_caller]
+    pub fn get_quarantined(&mut self, world: &mut World) -> EntityHandle {
+        let Some(handle) = self.try_get_quarantined(world) else {
+            self.exhausted("all entities in use");
+        };
+        handle
+    }
+
+    /// Like [`EntityPool::get_quarantined`] but returns `None` on pool exhaustion.
+    #[track_caller]
+    pub fn try_get_quarantined(&mut self, world: &mut World) -> Option<EntityHandle> {
+        self.debug_assert_world(world);
+
+        let handle = self.try_get_live(world)?;
+        quarantine(world, handle.entity());
+        Some(handle)
+    }
+}
+
+/// Marks `entity` with [`Quarantine`] and quarantines the components it already has.
+///
+/// # Panics
+/// Panics if `entity` doesn't exist.
+pub fn quarantine(world: &mut World, entity: Entity) {
+    let set = world.get_resource::<QuarantineSet>().cloned();
+    let mut entity = world.entity_mut(entity);
+    entity.insert(Quarantine);
+    if let Some(set) = set {
+        set.quarantine(&mut entity);
+    }
+}
+
+/// Removes [`Quarantine`] from `entity` and moves its quarantined components back in place. Does
+/// nothing for entities that aren't quarantined.
+///
+/// # Panics
+/// Panics if `entity` doesn't exist.
+pub fn release_quarantine(world: &mut World, entity: Entity) {
+    let set = world.get_resource::<QuarantineSet>().cloned();
+    let mut entity = world.entity_mut(entity);
+    if entity.take::<Quarantine>().is_none() {
+        return;
+    }
+    if let Some(set) = set {
+        set.release(&mut entity);
+    }
+}
+
+/// Quarantines the components of the [`QuarantineSet`] on every entity marked with [`Quarantine`].
+pub fn apply_quarantine(world: &mut World) {
+    let Some(set) = world.get_resource::<QuarantineSet>().cloned() else {
+        return;
+    };
+
+    let quarantined: Vec<Entity> = world
+        .query_filtered::<Entity, With<Quarantine>>()
+        .iter(world)
+        .collect();
+    for entity in quarantined {
+        set.quarantine(&mut world.entity_mut(entity));
+    }
+}
+
+fn quarantine_component<T: Component>(entity: &mut EntityWorldMut) {
+    if let Some(component) = entity.take::<T>() {
+        entity.insert(Quarantined(component));
+    }
+}
+
+fn release_component<T: Component>(entity: &mut EntityWorldMut) {
+    if let Some(Quarantined(component)) = entity.take::<Quarantined<T>>() {
+        entity.insert(component);
+    }
+}
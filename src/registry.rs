@@ -0,0 +1,171 @@
+use bevy::ecs::{entity::Entity, system::Resource};
+use std::{
+    fmt,
+    ops::Range,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, Weak,
+    },
+};
+
+/// Unique identifier of an [`EntityPool`](crate::EntityPool) within the process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PoolId(u64);
+
+impl PoolId {
+    pub(crate) fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Index ranges and the pools owning them.
+type OwnedRanges = Vec<(Range<u32>, PoolId)>;
+
+/// Records which entity index ranges each pool in a world owns.
+///
+/// When present in a world, [`EntityPool::new`](crate::EntityPool::new) and
+/// [`EntityPool::grow`](crate::EntityPool::grow) register the pool's entities and panic on overlap
+/// with another pool - two pools sharing entities otherwise shows up as component corruption much
+/// later. Overlap is checked on entity indices, regardless of generation.
+///
+/// Registries are per world - pools of different worlds may own the same indices. A pool's ranges
+/// are released when it is dropped, or through [`PoolRegistry::unregister`].
+#[derive(Resource, Default, Debug)]
+pub struct PoolRegistry {
+    /// Sorted by range start, ranges never overlap and a pool's adjacent ranges are merged. Shared with the [`Registration`]s of pools.
+    ranges: Arc<Mutex<OwnedRanges>>,
+}
+
+/// Held by a registered pool, releases the pool's ranges when dropped.
+#[derive(Debug)]
+pub(crate) struct Registration {
+    ranges: Weak<Mutex<OwnedRanges>>,
+    pool: PoolId,
+}
+
+impl Registration {
+    /// Returns `true` if the registration releases ranges of `registry`.
+    pub(crate) fn of(&self, registry: &PoolRegistry) -> bool {
+        std::ptr::eq(self.ranges.as_ptr(), Arc::as_ptr(&registry.ranges))
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // the registry may have been removed from its world already
+        if let Some(ranges) = self.ranges.upgrade() {
+            lock_ranges(&ranges).retain(|(_, owner)| *owner != self.pool);
+        }
+    }
+}
+
+fn lock_ranges(ranges: &Mutex<OwnedRanges>) -> MutexGuard<'_, OwnedRanges> {
+    // updates don't panic halfway, a poisoned lock still holds sound ranges
+    ranges.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Returned when registering a range that is already owned by another pool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeOverlap {
+    pub pool: PoolId,
+    pub range: Range<u32>,
+    pub owner: PoolId,
+    pub owned: Range<u32>,
+}
+
+impl fmt::Display for RangeOverlap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} can't reserve entity indices {:?} - {:?} already owns {:?}",
+            self.pool, self.range, self.owner, self.owned
+        )
+    }
+}
+
+impl std::error::Error for RangeOverlap {}
+
+impl PoolRegistry {
+    /// Registers `entities` as owned by `pool`. Nothing is registered if any of them overlaps a
+    /// range owned by another pool. Registering indices `pool` already owns is a no-op.
+    pub fn register(&mut self, pool: PoolId, entities: &[Entity]) -> Result<(), RangeOverlap> {
+        let new = index_ranges(entities);
+        let mut ranges = lock_ranges(&self.ranges);
+        for range in &new {
+            if let Some((owned, owner)) = ranges.iter().find(|(owned, owner)| {
+                *owner != pool && owned.start < range.end && range.start < owned.end
+            }) {
+                return Err(RangeOverlap {
+                    pool,
+                    range: range.clone(),
+                    owner: *owner,
+                    owned: owned.clone(),
+                });
+            }
+        }
+
+        ranges.extend(new.into_iter().map(|range| (range, pool)));
+        ranges.sort_by_key(|(range, _)| range.start);
+        // indices `pool` registers again merge into the range it already owns
+        ranges.dedup_by(|(range, owner), (previous, previous_owner)| {
+            let merge = owner == previous_owner && range.start <= previous.end;
+            if merge {
+                previous.end = previous.end.max(range.end);
+            }
+            merge
+        });
+        Ok(())
+    }
+
+    /// Releases every range owned by `pool`.
+    pub fn unregister(&mut self, pool: PoolId) {
+        lock_ranges(&self.ranges).retain(|(_, owner)| *owner != pool);
+    }
+
+    /// Guard releasing `pool`'s ranges once dropped.
+    pub(crate) fn registration(&self, pool: PoolId) -> Registration {
+        Registration {
+            ranges: Arc::downgrade(&self.ranges),
+            pool,
+        }
+    }
+
+    /// Returns the pool owning `entity`'s index.
+    pub fn owner(&self, entity: Entity) -> Option<PoolId> {
+        let index = entity.index();
+        let ranges = lock_ranges(&self.ranges);
+        let after = ranges.partition_point(|(range, _)| range.start <= index);
+        after
+            .checked_sub(1)
+            .map(|i| &ranges[i])
+            .filter(|(range, _)| range.contains(&index))
+            .map(|(_, owner)| *owner)
+    }
+
+    /// Index ranges owned by `pool`, in ascending order.
+    pub fn ranges(&self, pool: PoolId) -> impl Iterator<Item = Range<u32>> {
+        let owned: Vec<Range<u32>> = lock_ranges(&self.ranges)
+            .iter()
+            .filter(|(_, owner)| *owner == pool)
+            .map(|(range, _)| range.clone())
+            .collect();
+        owned.into_iter()
+    }
+}
+
+/// Collapses entity indices into sorted runs of consecutive indices.
+fn index_ranges(entities: &[Entity]) -> Vec<Range<u32>> {
+    let mut indices: Vec<u32> = entities.iter().map(|entity| entity.index()).collect();
+    indices.sort_unstable();
+    indices.dedup();
+
+    let mut ranges: Vec<Range<u32>> = Vec::new();
+    for index in indices {
+        match ranges.last_mut() {
+            Some(range) if range.end == index => range.end += 1,
+            _ => ranges.push(index..index + 1),
+        }
+    }
+    ranges
+}
@@ -0,0 +1,171 @@
+use crate::{task::scratch_world, ScratchContext};
+use bevy::{
+    ecs::{
+        entity::{Entity, EntityHashMap},
+        reflect::AppTypeRegistry,
+        world::World,
+    },
+    log::error,
+    scene::{DynamicScene, DynamicSceneBuilder},
+};
+use std::sync::{Arc, Mutex};
+
+type ScratchStep = Arc<dyn Fn(&mut ScratchContext) + Send + Sync>;
+
+/// A pooled task split into named steps so it can be recorded and replayed.
+///
+/// ```ignore
+/// let (task, recording) = ScratchSteps::new()
+///     .step("terrain", generate_terrain)
+///     .step("erosion", erode)
+///     .record();
+/// tasks.spawn(&mut pool, &type_registry, 64, task);
+/// ```
+#[derive(Clone, Default)]
+pub struct ScratchSteps {
+    steps: Vec<(String, ScratchStep)>,
+}
+
+impl ScratchSteps {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a step. Steps run in order and must draw randomness from
+    /// [`ScratchContext::rng`] only for replays to reproduce them.
+    pub fn step(
+        mut self,
+        label: impl Into<String>,
+        step: impl Fn(&mut ScratchContext) + Send + Sync + 'static,
+    ) -> Self {
+        self.steps.push((label.into(), Arc::new(step)));
+        self
+    }
+
+    /// Returns a task running every step, ready to be spawned on
+    /// [`PooledTasks`](crate::PooledTasks), along with a handle receiving its recording once the
+    /// task started.
+    pub fn record(
+        self,
+    ) -> (
+        impl FnOnce(&mut ScratchContext) + Send + 'static,
+        PendingRecording,
+    ) {
+        let pending = PendingRecording::default();
+        let slot = pending.0.clone();
+        let task = move |context: &mut ScratchContext| {
+            let snapshot = DynamicSceneBuilder::from_world(&context.world)
+                .extract_entities(context.entities().iter().copied())
+                .build();
+            *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(ScratchRecording {
+                seed: context.seed(),
+                entities: context.entities().to_vec(),
+                snapshot: Arc::new(snapshot),
+                steps: self.steps.clone(),
+            });
+
+            for (_, step) in &self.steps {
+                step(context);
+            }
+        };
+        (task, pending)
+    }
+}
+
+/// Receives the [`ScratchRecording`] of a task created with [`ScratchSteps::record`].
+#[derive(Clone, Default)]
+pub struct PendingRecording(Arc<Mutex<Option<ScratchRecording>>>);
+
+impl PendingRecording {
+    /// Returns the recording, `None` until the task started.
+    pub fn get(&self) -> Option<ScratchRecording> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Inputs and operations of a pooled task - the scratch world right after copy-in, the RNG seed
+/// and the steps that ran on it.
+#[derive(Clone)]
+pub struct ScratchRecording {
+    seed: u64,
+    entities: Vec<Entity>,
+    snapshot: Arc<DynamicScene>,
+    steps: Vec<(String, ScratchStep)>,
+}
+
+impl ScratchRecording {
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Labels of the recorded steps, in order.
+    pub fn steps(&self) -> impl Iterator<Item = &str> {
+        self.steps.iter().map(|(label, _)| label.as_str())
+    }
+
+    /// Restores the recorded scratch world and RNG for stepping through the task again.
+    ///
+    /// Components of the snapshot must be registered in `type_registry`.
+    pub fn replay(&self, type_registry: &AppTypeRegistry) -> Replay {
+        let mut world = scratch_world(type_registry, &self.entities);
+        let mut entity_map: EntityHashMap<Entity> = self.entities.iter().map(|&e| (e, e)).collect();
+        if let Err(e) = self.snapshot.write_to_world(&mut world, &mut entity_map) {
+            error!("Failed to restore recorded scratch world: {e}");
+        }
+
+        Replay {
+            context: ScratchContext::new(world, self.entities.clone(), self.seed),
+            steps: self.steps.clone(),
+            next: 0,
+        }
+    }
+}
+
+/// Step-by-step re-execution of a [`ScratchRecording`], runs on the calling thread. The scratch
+/// world can be inspected between steps.
+pub struct Replay {
+    context: ScratchContext,
+    steps: Vec<(String, ScratchStep)>,
+    next: usize,
+}
+
+impl Replay {
+    /// Runs the next step and returns its label, `None` once every step ran.
+    pub fn step(&mut self) -> Option<&str> {
+        let (label, step) = self.steps.get(self.next)?;
+        self.next += 1;
+        step(&mut self.context);
+        Some(label)
+    }
+
+    /// Runs the remaining steps.
+    pub fn run_to_end(&mut self) {
+        while self.step().is_some() {}
+    }
+
+    /// Label of the step [`Replay::step`] runs next.
+    pub fn next_step(&self) -> Option<&str> {
+        self.steps.get(self.next).map(|(label, _)| label.as_str())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == self.steps.len()
+    }
+
+    pub fn world(&self) -> &World {
+        &self.context.world
+    }
+
+    /// Context handed to the steps - changes made through it are seen by later steps.
+    pub fn context_mut(&mut self) -> &mut ScratchContext {
+        &mut self.context
+    }
+
+    pub fn into_context(self) -> ScratchContext {
+        self.context
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{EntityHandle, EntityPool};
+use bevy::{
+    ecs::{
+        entity::{Entities, Entity},
+        world::World,
+    },
+    log::warn,
+};
+use std::{
+    fmt,
+    sync::{Mutex, MutexGuard},
+};
+
+/// Entities reserved through [`EntityPool::reserve`] that haven't been flushed into the pool yet.
+#[derive(Default)]
+pub(crate) struct PendingReservations(Mutex<Vec<Entity>>);
+
+impl PendingReservations {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Vec<Entity>> {
+        // reservations are only ever appended or taken as a whole
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl EntityPool {
+    /// Reserves `count` additional entities without exclusive world access, using Bevy's lock-free
+    /// entity reservation. Works from any system with `Res<EntityPool>` and `&Entities`.
+    ///
+    /// The entities only become available to [`EntityPool::get`] once [`EntityPool::flush`] runs -
+    /// [`PooledTaskPlugin`](crate::PooledTaskPlugin) does so every frame. `entities` must be those
+    /// of the pool's world - `Entities` doesn't know its world, so this can't be checked.
+    pub fn reserve(&self, entities: &Entities, count: u32) {
+        self.pending.lock().extend(entities.reserve_entities(count));
+    }
+
+    /// Number of reserved entities waiting for [`EntityPool::flush`].
+    pub fn pending_reservations(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    /// Points at reservations that would have satisfied an acquisition that ran out.
+    pub(crate) fn warn_pending_reservations(&self) {
+        let pending = self.pending_reservations();
+        if pending > 0 {
+            warn!("pool exhausted, {pending} reserved entities wait for EntityPool::flush");
+        }
+    }
+
+    /// Flushes the world's reserved entities and adds entities reserved through
+    /// [`EntityPool::reserve`] to the pool, so every pooled entity exists before it's handed out.
+    ///
+    /// Entities reserved through `Entities` or `Commands` don't exist until the world is flushed -
+    /// call this after mixing those APIs with the pool outside of schedules.
+    ///
+    /// # Panics
+    /// Panics if the world has a [`PoolRegistry`](crate::PoolRegistry) and the entities overlap
+    /// another pool's. In debug builds, also panics if `world` isn't the pool's world.
+    pub fn flush(&mut self, world: &mut World) {
+        self.debug_assert_world(world);
+
+        let pending = std::mem::take(&mut *self.pending.lock());
+        if pending.is_empty() {
+            // `World::flush` isn't public - spawning flushes before anything else
+            world
+                .insert_or_spawn_batch(std::iter::empty::<(Entity, ())>())
+                .expect("spawning nothing can't fail");
+        } else {
+            self.grow(pending, world);
+        }
+    }
+
+    /// Flushes the pool, then returns a handle to an entity that is guaranteed to exist in `world`.
+    ///
+    /// # Panics
+    /// Panics on pool exhaustion, or if the entity was despawned behind the pool's back - see
+    /// [`PoolGuardPlugin`](crate::PoolGuardPlugin).
+    #[track_caller]
+    pub fn get_live(&mut self, world: &mut World) -> EntityHandle {
+        let Some(handle) = self.try_get_live(world) else {
+            self.exhausted("all entities in use");
+        };
+        handle
+    }
+
+    /// Like [`EntityPool::get_live`], returning `None` on pool exhaustion.
+    ///
+    /// # Panics
+    /// Panics if the entity was despawned behind the pool's back.
+    #[track_caller]
+    pub fn try_get_live(&mut self, world: &mut World) -> Option<EntityHandle> {
+        match self.try_get_live_checked(world) {
+            Ok(handle) => Some(handle),
+            Err(LiveAcquireError::Exhausted) => None,
+            Err(LiveAcquireError::Despawned(entity)) => panic!(
+                "pooled entity {entity:?} doesn't exist - it was despawned behind the pool's back"
+            ),
+        }
+    }
+
+    /// Like [`EntityPool::try_get_live`], returning an error instead of panicking if the entity
+    /// was despawned behind the pool's back. Its slot stays free, for
+    /// [`EntityPool::recover`] to reserve the entity again.
+    #[track_caller]
+    pub fn try_get_live_checked(
+        &mut self,
+        world: &mut World,
+    ) -> Result<EntityHandle, LiveAcquireError> {
+        self.flush(world);
+
+        let Some(slot) = self.allocator.allocate() else {
+            self.record_failure();
+            return Err(LiveAcquireError::Exhausted);
+        };
+        let entity = self.entities[slot as usize];
+        if world.get_entity(entity).is_none() {
+            self.allocator.free(slot);
+            return Err(LiveAcquireError::Despawned(entity));
+        }
+        Ok(self.checkout(slot))
+    }
+}
+
+/// Why [`EntityPool::try_get_live_checked`] couldn't hand out an entity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LiveAcquireError {
+    /// All entities are in use.
+    Exhausted,
+    /// The entity picked was despawned behind the pool's back.
+    Despawned(Entity),
+}
+
+impl fmt::Display for LiveAcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exhausted => write!(f, "all entities in use"),
+            Self::Despawned(entity) => write!(f, "pooled entity {entity:?} was despawned"),
+        }
+    }
+}
+
+impl std::error::Error for LiveAcquireError {}
@@ -0,0 +1,73 @@
+use crate::{handle::ReleaseQueue, EntityPool, PooledTasks};
+use bevy::ecs::world::World;
+
+impl EntityPool {
+    /// Reinitializes the pool in place, as if freshly constructed with the same entities - e.g.
+    /// between test cases or independent bake jobs sharing a world.
+    ///
+    /// Reservations waiting for [`EntityPool::flush`] are added first. Every slot is then cleared
+    /// (and reset to the template, if any) and despawned entities are spawned again. Pool
+    /// generations, statistics, leak reports and the event log start over. Configuration -
+    /// allocator, template, leak detection, watermark callbacks, task concurrency and the maximum
+    /// generation age - is kept.
+    ///
+    /// Outstanding handles are cut loose: dropping them no longer affects the pool. Every slot's
+    /// generation is bumped, so keys taken before the reset stay invalid. In flight
+    /// [`PooledTasks`] jobs on this pool's entities are cancelled instead of later copying back onto
+    /// re-issued slots.
+    ///
+    /// # Panics
+    /// Panics if a despawned entity can't be spawned again because its index was reused, or if
+    /// reservations overlap another pool's, see [`PoolRegistry`](crate::PoolRegistry).
+    pub fn reset(&mut self, world: &mut World) {
+        self.debug_assert_world(world);
+
+        self.flush(world);
+        if let Some(mut tasks) = world.get_resource_mut::<PooledTasks>() {
+            tasks.cancel_owned_by(self);
+        }
+        // handles from before the reset push into the old queue from now on
+        self.releases = ReleaseQueue::default();
+
+        let capacity = self.entities.len();
+        for slot in 0..capacity as u32 {
+            let entity = self.entities[slot as usize];
+            match world.get_entity_mut(entity) {
+                Some(mut entity) => {
+                    entity.retain::<()>();
+                }
+                None => {
+                    assert!(
+                        world.get_or_spawn(entity).is_some(),
+                        "can't respawn pooled entity {entity:?} - its index was reused"
+                    );
+                }
+            }
+            self.apply_template(slot, world);
+            self.record_freed(slot);
+        }
+
+        for generation in &mut self.generations {
+            *generation = generation.wrapping_add(1);
+        }
+        self.in_use = vec![false; capacity];
+        self.conflicted.clear();
+        self.published = vec![false; capacity];
+        self.allocator.clear();
+        self.allocator.grow(0..capacity as u32);
+        self.stats.reset();
+        self.ages.reset(capacity);
+        if let Some(acquired_at) = &mut self.acquired_at {
+            *acquired_at = vec![None; capacity];
+        }
+        self.leaks.clear();
+        if let Some(log) = &mut self.event_log {
+            log.clear();
+        }
+        #[cfg(feature = "profiling")]
+        {
+            self.profiler = crate::profiling::Profiler::with_capacity(capacity);
+        }
+        self.update_watermarks();
+    }
+}
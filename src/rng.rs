@@ -0,0 +1,63 @@
+use crate::PooledTaskId;
+use std::ops::Range;
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Small reproducible RNG handed to pooled tasks (SplitMix64).
+///
+/// Every task gets its own generator seeded from the task id and the seed set with
+/// [`PooledTasks::set_seed`](crate::PooledTasks::set_seed) - the seed is reported with
+/// [`PooledTaskFinished`](crate::PooledTaskFinished) so any result can be regenerated with
+/// [`PooledTasks::spawn_with_seed`](crate::PooledTasks::spawn_with_seed). Not cryptographically
+/// secure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScratchRng {
+    state: u64,
+}
+
+impl ScratchRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Uniform in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `range`.
+    ///
+    /// # Panics
+    /// Panics if `range` is empty.
+    pub fn range(&mut self, range: Range<u32>) -> u32 {
+        assert!(!range.is_empty(), "empty range {range:?}");
+        let span = u64::from(range.end - range.start);
+        // multiply-shift instead of modulo - bias is negligible for 32 bit spans
+        range.start + ((u64::from(self.next_u32()) * span) >> 32) as u32
+    }
+
+    /// Returns `true` with probability `p`.
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+}
+
+/// Derives the seed of task `id` from the user seed.
+pub(crate) fn task_seed(seed: u64, id: PooledTaskId) -> u64 {
+    mix(seed ^ id.0.wrapping_mul(GOLDEN_GAMMA))
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,141 @@
+use bevy::ecs::{
+    system::{BoxedSystem, IntoSystem},
+    world::{World, WorldId},
+};
+use std::borrow::Cow;
+
+/// What [`ScratchSchedule`] does after its inspector returns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepControl {
+    Continue,
+    /// Stop before the next system - [`ScratchSchedule::run`] returns and the next call resumes.
+    Pause,
+}
+
+/// Passed to the inspector after every system.
+#[derive(Clone, Debug)]
+pub struct SystemStep {
+    /// Position of the system that just ran.
+    pub index: usize,
+    /// Name of the system that just ran.
+    pub system: Cow<'static, str>,
+    /// Systems left in this run.
+    pub remaining: usize,
+}
+
+/// Result of [`ScratchSchedule::run`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleRun {
+    Finished,
+    /// Paused by the inspector, `next` is the index of the system the next run resumes at.
+    Paused {
+        next: usize,
+    },
+}
+
+type Inspector = Box<dyn FnMut(&SystemStep, &mut World) -> StepControl + Send + Sync>;
+
+/// Runs systems on a scratch world one after another on the calling thread, applying their
+/// commands after each - works on single threaded and wasm targets where bevy's multi-threaded
+/// executor isn't available.
+///
+/// Systems can't be interrupted, but the schedule can pause between them: an inspector set with
+/// [`ScratchSchedule::set_inspector`] sees the world after every system and decides whether to
+/// continue, so a misbehaving generator can be stepped through system by system.
+#[derive(Default)]
+pub struct ScratchSchedule {
+    systems: Vec<BoxedSystem>,
+    /// World the systems were initialized for.
+    world_id: Option<WorldId>,
+    /// Number of leading systems initialized for `world_id`, systems added later still need it.
+    initialized: usize,
+    next: usize,
+    inspector: Option<Inspector>,
+}
+
+impl ScratchSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `system`, it runs after the systems added before.
+    pub fn add_system<M>(&mut self, system: impl IntoSystem<(), (), M>) -> &mut Self {
+        self.systems.push(Box::new(IntoSystem::into_system(system)));
+        self
+    }
+
+    /// Calls `inspector` after every system with the scratch world, replacing the previous one.
+    pub fn set_inspector(
+        &mut self,
+        inspector: impl FnMut(&SystemStep, &mut World) -> StepControl + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.inspector = Some(Box::new(inspector));
+        self
+    }
+
+    pub fn clear_inspector(&mut self) -> &mut Self {
+        self.inspector = None;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Returns `true` if the last run was paused and the next one resumes mid-schedule.
+    pub fn is_paused(&self) -> bool {
+        self.next != 0
+    }
+
+    /// Runs the systems in order - from where it paused, if it did.
+    ///
+    /// # Panics
+    /// Panics when resuming a paused run on a different world.
+    pub fn run(&mut self, world: &mut World) -> ScheduleRun {
+        if self.world_id != Some(world.id()) {
+            assert!(
+                !self.is_paused(),
+                "paused scratch schedule resumed on a different world"
+            );
+            self.world_id = Some(world.id());
+            self.initialized = 0;
+        }
+        for system in &mut self.systems[self.initialized..] {
+            system.initialize(world);
+        }
+        self.initialized = self.systems.len();
+
+        while self.next < self.systems.len() {
+            let index = self.next;
+            self.next += 1;
+
+            let system = &mut self.systems[index];
+            system.run((), world);
+            system.apply_deferred(world);
+
+            let Some(inspector) = &mut self.inspector else {
+                continue;
+            };
+            let step = SystemStep {
+                index,
+                system: system.name(),
+                remaining: self.systems.len() - self.next,
+            };
+            if inspector(&step, world) == StepControl::Pause && self.next < self.systems.len() {
+                return ScheduleRun::Paused { next: self.next };
+            }
+        }
+
+        self.next = 0;
+        ScheduleRun::Finished
+    }
+
+    /// Runs until the schedule finished, ignoring pauses.
+    pub fn run_to_end(&mut self, world: &mut World) {
+        while self.run(world) != ScheduleRun::Finished {}
+    }
+}
@@ -0,0 +1,230 @@
+//! Dynamic, reflection-driven pool API meant to be bound from scripting crates.
+//!
+//! Scripts only ever see [`RawSlot`]s - plain `(index, generation)` pairs that are validated on
+//! every call. Components are inserted through reflection, so mods can drive scratch generation
+//! without compile-time knowledge of the component types. Every type passed to [`insert_reflect`]
+//! must be registered in the world's [`AppTypeRegistry`] with `#[reflect(Component)]`.
+//!
+//! ```ignore
+//! let mut pools = ScriptPools::default();
+//! pools.register("props", EntityPool::new(entities, &mut world));
+//! world.insert_resource(pools);
+//!
+//! let slot = scripting::acquire_by_name(&mut world, "props")?;
+//! scripting::insert_reflect(&mut world, slot, Box::new(Transform::default()))?;
+//! scripting::free(&mut world, slot)?;
+//! ```
+
+use crate::{EntityHandle, EntityPool, LiveAcquireError, RawSlot, SlotKey};
+use bevy::{
+    ecs::{
+        entity::Entity,
+        reflect::{AppTypeRegistry, ReflectComponent},
+        system::Resource,
+        world::{Mut, World},
+    },
+    reflect::Reflect,
+    utils::HashMap,
+};
+use std::fmt;
+
+/// Named pools reachable from scripts, along with the handles scripts currently hold.
+///
+/// Script slots are allocated from a table of their own, so a [`RawSlot`] identifies a checkout
+/// across all registered pools without scripts having to know which pool it came from.
+#[derive(Resource, Default)]
+pub struct ScriptPools {
+    pools: HashMap<String, EntityPool>,
+    slots: Vec<ScriptSlot>,
+    free_slots: Vec<u32>,
+}
+
+#[derive(Default)]
+struct ScriptSlot {
+    generation: u32,
+    /// Name of the owning pool and the handle held on the script's behalf.
+    checkout: Option<(String, EntityHandle)>,
+}
+
+/// Errors surfaced to scripts instead of panicking.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScriptPoolError {
+    /// No [`ScriptPools`] resource in the world.
+    MissingResource,
+    /// No pool registered under the name.
+    UnknownPool(String),
+    /// The named pool has no free entities left.
+    Exhausted(String),
+    /// The slot was freed or never existed.
+    InvalidSlot(RawSlot),
+    /// The component's type isn't registered with `#[reflect(Component)]`.
+    UnregisteredComponent(String),
+    /// The named pool belongs to another world than the one passed in.
+    ForeignPool(String),
+    /// No [`AppTypeRegistry`] resource in the world.
+    MissingTypeRegistry,
+    /// A pooled entity was despawned behind the pool's back.
+    DespawnedEntity(Entity),
+}
+
+impl fmt::Display for ScriptPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingResource => write!(f, "no ScriptPools resource in world"),
+            Self::UnknownPool(name) => write!(f, "no pool named {name:?}"),
+            Self::Exhausted(name) => write!(f, "pool {name:?} exhausted - all entities in use"),
+            Self::InvalidSlot(slot) => {
+                write!(f, "invalid slot {}v{}", slot.index, slot.generation)
+            }
+            Self::UnregisteredComponent(type_path) => {
+                write!(f, "{type_path} is not a registered reflected component")
+            }
+            Self::ForeignPool(name) => write!(f, "pool {name:?} belongs to another world"),
+            Self::MissingTypeRegistry => write!(f, "no AppTypeRegistry resource in world"),
+            Self::DespawnedEntity(entity) => write!(f, "pooled entity {entity:?} was despawned"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptPoolError {}
+
+impl ScriptPools {
+    /// Makes `pool` available to scripts under `name`, replacing any pool already registered under
+    /// it. `pool` must belong to the world this resource lives in.
+    ///
+    /// # Panics
+    /// Panics if `pool` belongs to another world than the pools registered before.
+    pub fn register(&mut self, name: impl Into<String>, pool: EntityPool) {
+        if let Some(other) = self
+            .pools
+            .values()
+            .find(|other| other.world_id() != pool.world_id())
+        {
+            panic!(
+                "{:?} belongs to {:?}, but registered pools belong to {:?}",
+                pool.id(),
+                pool.world_id(),
+                other.world_id()
+            );
+        }
+        self.pools.insert(name.into(), pool);
+    }
+
+    /// Returns the pool registered under `name`.
+    pub fn pool(&self, name: &str) -> Option<&EntityPool> {
+        self.pools.get(name)
+    }
+
+    /// Returns the pool registered under `name` mutably.
+    pub fn pool_mut(&mut self, name: &str) -> Option<&mut EntityPool> {
+        self.pools.get_mut(name)
+    }
+
+    /// Returns the entity behind a script slot, or `None` if the slot is stale.
+    pub fn entity(&self, slot: RawSlot) -> Option<Entity> {
+        self.handle(slot).map(EntityHandle::entity)
+    }
+
+    /// Reclaims entities released by scripts (or marked with [`Reclaim`](crate::Reclaim)) in every
+    /// registered pool.
+    pub fn reclaim(&mut self, world: &mut World) {
+        for pool in self.pools.values_mut() {
+            pool.reclaim(world);
+        }
+    }
+
+    fn handle(&self, slot: RawSlot) -> Option<&EntityHandle> {
+        self.slots
+            .get(slot.index as usize)
+            .filter(|script_slot| script_slot.generation == slot.generation)
+            .and_then(|script_slot| script_slot.checkout.as_ref())
+            .map(|(_, handle)| handle)
+    }
+
+    fn acquire(&mut self, name: &str, world: &mut World) -> Result<RawSlot, ScriptPoolError> {
+        let pool = self
+            .pools
+            .get_mut(name)
+            .ok_or_else(|| ScriptPoolError::UnknownPool(name.to_string()))?;
+        if !pool.belongs_to(world) {
+            return Err(ScriptPoolError::ForeignPool(name.to_string()));
+        }
+        let handle = pool.try_get_live_checked(world).map_err(|e| match e {
+            LiveAcquireError::Exhausted => ScriptPoolError::Exhausted(name.to_string()),
+            LiveAcquireError::Despawned(entity) => ScriptPoolError::DespawnedEntity(entity),
+        })?;
+
+        let index = self.free_slots.pop().unwrap_or_else(|| {
+            self.slots.push(ScriptSlot::default());
+            self.slots.len() as u32 - 1
+        });
+        let script_slot = &mut self.slots[index as usize];
+        script_slot.checkout = Some((name.to_string(), handle));
+
+        Ok(SlotKey::new(index, script_slot.generation).to_raw())
+    }
+
+    fn release(&mut self, slot: RawSlot, world: &mut World) -> Result<(), ScriptPoolError> {
+        self.handle(slot)
+            .ok_or(ScriptPoolError::InvalidSlot(slot))?;
+
+        let script_slot = &mut self.slots[slot.index as usize];
+        let (name, handle) = script_slot.checkout.take().expect("validated above");
+        script_slot.generation = script_slot.generation.wrapping_add(1);
+        self.free_slots.push(slot.index);
+
+        // the pool can be replaced through `register` while scripts hold slots of the old one
+        if let Some(pool) = self.pools.get_mut(&name) {
+            pool.release_handle(handle, world);
+        }
+        Ok(())
+    }
+}
+
+/// Checks out an entity from the pool registered under `pool`.
+pub fn acquire_by_name(world: &mut World, pool: &str) -> Result<RawSlot, ScriptPoolError> {
+    if !world.contains_resource::<ScriptPools>() {
+        return Err(ScriptPoolError::MissingResource);
+    }
+
+    world.resource_scope(|world, mut pools: Mut<ScriptPools>| pools.acquire(pool, world))
+}
+
+/// Inserts a reflected component on the entity behind `slot`, replacing any existing value.
+pub fn insert_reflect(
+    world: &mut World,
+    slot: RawSlot,
+    component: Box<dyn Reflect>,
+) -> Result<(), ScriptPoolError> {
+    let entity = world
+        .get_resource::<ScriptPools>()
+        .ok_or(ScriptPoolError::MissingResource)?
+        .entity(slot)
+        .ok_or(ScriptPoolError::InvalidSlot(slot))?;
+
+    let type_registry = world
+        .get_resource::<AppTypeRegistry>()
+        .ok_or(ScriptPoolError::MissingTypeRegistry)?
+        .clone();
+    let type_registry = type_registry.read();
+    let type_path = component.reflect_type_path();
+    let reflect_component = component
+        .get_represented_type_info()
+        .and_then(|info| type_registry.get_type_data::<ReflectComponent>(info.type_id()))
+        .ok_or_else(|| ScriptPoolError::UnregisteredComponent(type_path.to_string()))?;
+
+    let mut entity = world
+        .get_entity_mut(entity)
+        .ok_or(ScriptPoolError::DespawnedEntity(entity))?;
+    reflect_component.apply_or_insert(&mut entity, &*component, &type_registry);
+    Ok(())
+}
+
+/// Clears the entity behind `slot` and returns it to its pool. The slot is invalid afterwards.
+pub fn free(world: &mut World, slot: RawSlot) -> Result<(), ScriptPoolError> {
+    if !world.contains_resource::<ScriptPools>() {
+        return Err(ScriptPoolError::MissingResource);
+    }
+
+    world.resource_scope(|world, mut pools: Mut<ScriptPools>| pools.release(slot, world))
+}
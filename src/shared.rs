@@ -0,0 +1,188 @@
+use crate::{EntityHandle, EntityPool, SlotKey};
+use bevy::ecs::entity::Entity;
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+/// Thread-safe pool over a range of entities checked out from an [`EntityPool`], for running
+/// several generation tasks concurrently against the same reserved address space.
+///
+/// The range is carved into shards, each with its own free-list. Clones are cheap and each clone
+/// prefers one shard (see [`SharedEntityPool::with_shard`]), so tasks given different shards never
+/// contend. When its shard runs dry a clone steals from the other shards before failing. Released
+/// entities always go back to the shard that owns them.
+///
+/// Only entity addresses are shared - components live in each task's own scratch world and aren't
+/// cleared on release. The underlying main-world handles are returned to the [`EntityPool`] once the
+/// last clone and handle are dropped.
+#[derive(Clone)]
+pub struct SharedEntityPool {
+    inner: Arc<SharedInner>,
+    shard: usize,
+}
+
+struct SharedInner {
+    entities: Box<[Entity]>,
+    generations: Box<[AtomicU32]>,
+    shards: Box<[Mutex<Vec<u32>>]>,
+    // held so the main-world pool doesn't hand these entities out while they're shared
+    _handles: Vec<EntityHandle>,
+}
+
+impl EntityPool {
+    /// Checks out `count` entities and shares them across `shards` free-lists.
+    ///
+    /// # Panics
+    /// Panics on pool exhaustion or if `shards` is zero.
+    pub fn share(&mut self, count: usize, shards: usize) -> SharedEntityPool {
+        assert!(shards > 0, "shared pool needs at least one shard");
+
+        let handles: Vec<EntityHandle> = (0..count).map(|_| self.get()).collect();
+        let entities: Box<[Entity]> = handles.iter().map(EntityHandle::entity).collect();
+        let shards = (0..shards)
+            .map(|shard| {
+                let (start, end) = shard_range(shard, shards, count);
+                // reversed so slots are handed out in order
+                Mutex::new((start..end).rev().collect())
+            })
+            .collect();
+
+        SharedEntityPool {
+            inner: Arc::new(SharedInner {
+                generations: (0..count).map(|_| AtomicU32::new(0)).collect(),
+                entities,
+                shards,
+                _handles: handles,
+            }),
+            shard: 0,
+        }
+    }
+}
+
+impl SharedEntityPool {
+    /// Returns a clone that prefers acquiring from `shard`.
+    ///
+    /// # Panics
+    /// Panics if `shard` is out of range.
+    pub fn with_shard(&self, shard: usize) -> Self {
+        assert!(shard < self.shard_count(), "shard {shard} out of range");
+        Self {
+            inner: self.inner.clone(),
+            shard,
+        }
+    }
+
+    /// Number of shards the shared range was carved into.
+    pub fn shard_count(&self) -> usize {
+        self.inner.shards.len()
+    }
+
+    /// Total number of shared entities.
+    pub fn capacity(&self) -> usize {
+        self.inner.entities.len()
+    }
+
+    /// Returns a handle to an entity from the preferred shard, stealing from other shards if it's
+    /// empty.
+    ///
+    /// # Panics
+    /// Panics if every shard is exhausted.
+    pub fn get(&self) -> SharedEntityHandle {
+        self.try_get()
+            .unwrap_or_else(|| panic!("shared pool exhaustion - all entities in use"))
+    }
+
+    /// Like [`SharedEntityPool::get`] but returns `None` if every shard is exhausted.
+    pub fn try_get(&self) -> Option<SharedEntityHandle> {
+        let shards = self.shard_count();
+        (0..shards)
+            .map(|offset| (self.shard + offset) % shards)
+            .find_map(|shard| lock_shard(&self.inner.shards[shard]).pop())
+            .map(|slot| SharedEntityHandle {
+                entity: self.inner.entities[slot as usize],
+                key: SlotKey::new(
+                    slot,
+                    self.inner.generations[slot as usize].load(Ordering::Acquire),
+                ),
+                inner: self.inner.clone(),
+            })
+    }
+
+    /// Number of entities currently available across all shards.
+    pub fn available(&self) -> usize {
+        self.inner
+            .shards
+            .iter()
+            .map(|shard| lock_shard(shard).len())
+            .sum()
+    }
+}
+
+/// RAII guard over an entity from a [`SharedEntityPool`]. `Send + Sync`, returns the entity to its
+/// owning shard when dropped.
+pub struct SharedEntityHandle {
+    entity: Entity,
+    key: SlotKey,
+    inner: Arc<SharedInner>,
+}
+
+impl SharedEntityHandle {
+    /// The shared entity this handle grants access to.
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Key of this checkout within the shared pool - not interchangeable with keys of the
+    /// [`EntityPool`] the entities were shared from.
+    pub fn key(&self) -> SlotKey {
+        self.key
+    }
+}
+
+impl Deref for SharedEntityHandle {
+    type Target = Entity;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entity
+    }
+}
+
+impl fmt::Debug for SharedEntityHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedEntityHandle")
+            .field("entity", &self.entity)
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl Drop for SharedEntityHandle {
+    fn drop(&mut self) {
+        let slot = self.key.index();
+        self.inner.generations[slot as usize].fetch_add(1, Ordering::Release);
+
+        let shards = self.inner.shards.len();
+        let count = self.inner.entities.len();
+        let owner = (0..shards)
+            .find(|&shard| shard_range(shard, shards, count).1 > slot)
+            .expect("slot belongs to a shard");
+        lock_shard(&self.inner.shards[owner]).push(slot);
+    }
+}
+
+/// Slots `start..end` owned by `shard`.
+fn shard_range(shard: usize, shards: usize, count: usize) -> (u32, u32) {
+    let start = shard * count / shards;
+    let end = (shard + 1) * count / shards;
+    (start as u32, end as u32)
+}
+
+fn lock_shard(shard: &Mutex<Vec<u32>>) -> MutexGuard<'_, Vec<u32>> {
+    // free-lists are only ever pushed to or popped from, a panic can't leave them inconsistent
+    shard.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,231 @@
+use crate::{EntityHandle, EntityPool, SlotKey};
+use bevy::ecs::{
+    component::Component,
+    entity::Entity,
+    world::{Mut, World},
+};
+use std::{
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+/// `slotmap`-style collection of `T` stored on pooled entities - lets generation code written
+/// against slotmap or generational-arena keys run on ECS entities with minimal changes.
+///
+/// Owns its world and pool; values are `T` components on the pooled entities, so other components
+/// can be attached to [`PooledSlotMap::entity`] through [`PooledSlotMap::world_mut`]. Keys are
+/// [`SlotKey`]s and go stale once their value is removed.
+pub struct PooledSlotMap<T: Component> {
+    world: World,
+    pool: EntityPool,
+    /// Handles of occupied slots, by slot index.
+    handles: Vec<Option<EntityHandle>>,
+    len: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Component> PooledSlotMap<T> {
+    /// Creates a map with room for `capacity` values in a fresh world.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut world = World::new();
+        let entities = world.entities().reserve_entities(capacity as u32).collect();
+        let pool = EntityPool::new(entities, &mut world);
+        Self::from_parts(world, pool)
+    }
+
+    /// Wraps an existing world and pool, e.g. a task's scratch world. Entities already checked out
+    /// of `pool` are left alone.
+    pub fn from_parts(world: World, pool: EntityPool) -> Self {
+        Self {
+            handles: std::iter::repeat_with(|| None)
+                .take(pool.capacity())
+                .collect(),
+            world,
+            pool,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the world and pool. The map's values stay on their entities, which are queued for
+    /// reclamation - call [`EntityPool::reclaim`] to clear them.
+    pub fn into_parts(self) -> (World, EntityPool) {
+        (self.world, self.pool)
+    }
+
+    /// Inserts `value` and returns its key.
+    ///
+    /// # Panics
+    /// Panics if the map is full.
+    #[track_caller]
+    pub fn insert(&mut self, value: T) -> SlotKey {
+        match self.try_insert(value) {
+            Ok(key) => key,
+            Err(_) => self.pool.exhausted("all slots of the slot map in use"),
+        }
+    }
+
+    /// Inserts `value` and returns its key, or gives `value` back if the map is full.
+    #[track_caller]
+    pub fn try_insert(&mut self, value: T) -> Result<SlotKey, T> {
+        let Some(handle) = self.pool.try_get() else {
+            return Err(value);
+        };
+        let key = handle.key();
+        self.world.entity_mut(handle.entity()).insert(value);
+
+        // the pool may have grown through `pool_mut`
+        let index = key.index() as usize;
+        if index >= self.handles.len() {
+            self.handles.resize_with(index + 1, || None);
+        }
+        self.handles[index] = Some(handle);
+        self.len += 1;
+        Ok(key)
+    }
+
+    /// Removes and returns the value behind `key`, `None` if the key is stale.
+    pub fn remove(&mut self, key: SlotKey) -> Option<T> {
+        self.handle(key)?;
+        let handle = self.handles[key.index() as usize].take()?;
+        let value = self.world.entity_mut(handle.entity()).take::<T>();
+        self.pool.release_handle(handle, &mut self.world);
+        self.len -= 1;
+        value
+    }
+
+    pub fn get(&self, key: SlotKey) -> Option<&T> {
+        self.world.get::<T>(self.entity(key)?)
+    }
+
+    pub fn get_mut(&mut self, key: SlotKey) -> Option<&mut T> {
+        let entity = self.entity(key)?;
+        self.world.get_mut::<T>(entity).map(Mut::into_inner)
+    }
+
+    pub fn contains_key(&self, key: SlotKey) -> bool {
+        self.handle(key).is_some()
+    }
+
+    /// Entity holding the value behind `key`.
+    pub fn entity(&self, key: SlotKey) -> Option<Entity> {
+        self.handle(key).map(EntityHandle::entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.pool.capacity()
+    }
+
+    /// Removes every value, invalidating all keys.
+    pub fn clear(&mut self) {
+        for handle in self.handles.iter_mut().filter_map(Option::take) {
+            self.pool.release_handle(handle, &mut self.world);
+        }
+        self.len = 0;
+    }
+
+    /// Keys and values in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (SlotKey, &T)> + '_ {
+        self.handles.iter().flatten().filter_map(|handle| {
+            let value = self.world.get::<T>(handle.entity())?;
+            Some((handle.key(), value))
+        })
+    }
+
+    /// Keys in slot order.
+    pub fn keys(&self) -> impl Iterator<Item = SlotKey> + '_ {
+        self.handles.iter().flatten().map(EntityHandle::key)
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn pool(&self) -> &EntityPool {
+        &self.pool
+    }
+
+    /// The pool, e.g. to [`EntityPool::grow`] it with entities of [`PooledSlotMap::world_mut`].
+    pub fn pool_mut(&mut self) -> &mut EntityPool {
+        &mut self.pool
+    }
+
+    fn handle(&self, key: SlotKey) -> Option<&EntityHandle> {
+        self.handles
+            .get(key.index() as usize)?
+            .as_ref()
+            .filter(|handle| handle.key() == key && self.pool.is_valid(key))
+    }
+}
+
+impl<T: Component> Index<SlotKey> for PooledSlotMap<T> {
+    type Output = T;
+
+    /// # Panics
+    /// Panics if `key` is stale.
+    fn index(&self, key: SlotKey) -> &T {
+        match self.get(key) {
+            Some(value) => value,
+            None => panic!("invalid slot map key {key:?}"),
+        }
+    }
+}
+
+impl<T: Component> IndexMut<SlotKey> for PooledSlotMap<T> {
+    fn index_mut(&mut self, key: SlotKey) -> &mut T {
+        match self.get_mut(key) {
+            Some(value) => value,
+            None => panic!("invalid slot map key {key:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handle::lock_releases;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Value(u32);
+
+    #[test]
+    fn removing_leaves_no_queued_releases() {
+        let mut map = PooledSlotMap::<Value>::with_capacity(2);
+        for i in 0..100 {
+            let key = map.insert(Value(i));
+            assert_eq!(map.remove(key), Some(Value(i)));
+        }
+        let a = map.insert(Value(1));
+        map.insert(Value(2));
+        map.clear();
+
+        assert!(lock_releases(&map.pool.releases).is_empty());
+        assert!(map.is_empty());
+        assert_eq!(map.get(a), None);
+        assert_eq!(map.pool.stats().in_use, 0);
+    }
+
+    #[test]
+    fn stale_keys_miss_reused_slots() {
+        let mut map = PooledSlotMap::<Value>::with_capacity(1);
+        let old = map.insert(Value(1));
+        map.remove(old);
+        let new = map.insert(Value(2));
+
+        assert!(!map.contains_key(old));
+        assert_eq!(map.remove(old), None);
+        assert_eq!(map[new], Value(2));
+    }
+}
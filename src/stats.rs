@@ -0,0 +1,117 @@
+/// Snapshot of pool usage returned by [`EntityPool::stats`](crate::EntityPool::stats).
+///
+/// The smoothed fields are exponentially weighted moving averages updated once per call to
+/// [`EntityPool::sample_stats`](crate::EntityPool::sample_stats). They are meant for adaptive
+/// systems (e.g. lowering generation detail under scratch pressure) that would otherwise react to
+/// noisy per-frame counts.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PoolStats {
+    /// Total number of entities reserved by the pool.
+    pub capacity: usize,
+    /// Number of entities currently handed out.
+    pub in_use: usize,
+    /// Highest number of entities handed out at once.
+    pub peak_in_use: usize,
+    /// Number of successful acquisitions over the lifetime of the pool.
+    pub total_acquisitions: u64,
+    /// Number of acquisitions that failed because the pool was exhausted.
+    pub failed_acquisitions: u64,
+    /// Smoothed fraction of the pool in use, in `0.0..=1.0`.
+    pub smoothed_occupancy: f32,
+    /// Smoothed number of acquisitions per sample.
+    pub smoothed_acquire_rate: f32,
+}
+
+/// Default weight given to the newest sample.
+pub const DEFAULT_STATS_SMOOTHING: f32 = 0.1;
+
+pub(crate) struct StatsTracker {
+    smoothing: f32,
+    primed: bool,
+    acquisitions_since_sample: u64,
+    peak_in_use: usize,
+    total_acquisitions: u64,
+    failed_acquisitions: u64,
+    smoothed_occupancy: f32,
+    smoothed_acquire_rate: f32,
+}
+
+impl Default for StatsTracker {
+    fn default() -> Self {
+        Self {
+            smoothing: DEFAULT_STATS_SMOOTHING,
+            primed: false,
+            acquisitions_since_sample: 0,
+            peak_in_use: 0,
+            total_acquisitions: 0,
+            failed_acquisitions: 0,
+            smoothed_occupancy: 0.0,
+            smoothed_acquire_rate: 0.0,
+        }
+    }
+}
+
+impl StatsTracker {
+    /// Forgets everything recorded so far, keeping the smoothing factor.
+    pub(crate) fn reset(&mut self) {
+        *self = Self {
+            smoothing: self.smoothing,
+            ..Self::default()
+        };
+    }
+
+    pub(crate) fn set_smoothing(&mut self, smoothing: f32) {
+        assert!(
+            smoothing > 0.0 && smoothing <= 1.0,
+            "stats smoothing factor must be in (0, 1], got {smoothing}"
+        );
+        self.smoothing = smoothing;
+    }
+
+    pub(crate) fn record_acquire(&mut self, in_use: usize) {
+        self.acquisitions_since_sample += 1;
+        self.total_acquisitions += 1;
+        self.peak_in_use = self.peak_in_use.max(in_use);
+    }
+
+    pub(crate) fn record_failure(&mut self) {
+        self.failed_acquisitions += 1;
+    }
+
+    pub(crate) fn sample(&mut self, in_use: usize, capacity: usize) {
+        let occupancy = occupancy(in_use, capacity);
+        let rate = self.acquisitions_since_sample as f32;
+        self.acquisitions_since_sample = 0;
+
+        // seed the averages with the first sample instead of decaying up from zero
+        if !self.primed {
+            self.primed = true;
+            self.smoothed_occupancy = occupancy;
+            self.smoothed_acquire_rate = rate;
+            return;
+        }
+
+        self.smoothed_occupancy += self.smoothing * (occupancy - self.smoothed_occupancy);
+        self.smoothed_acquire_rate += self.smoothing * (rate - self.smoothed_acquire_rate);
+    }
+
+    pub(crate) fn snapshot(&self, in_use: usize, capacity: usize) -> PoolStats {
+        PoolStats {
+            capacity,
+            in_use,
+            peak_in_use: self.peak_in_use,
+            total_acquisitions: self.total_acquisitions,
+            failed_acquisitions: self.failed_acquisitions,
+            smoothed_occupancy: self.smoothed_occupancy,
+            smoothed_acquire_rate: self.smoothed_acquire_rate,
+        }
+    }
+}
+
+pub(crate) fn occupancy(in_use: usize, capacity: usize) -> f32 {
+    if capacity == 0 {
+        0.0
+    } else {
+        in_use as f32 / capacity as f32
+    }
+}
@@ -0,0 +1,696 @@
+use crate::{
+    concurrency::ConcurrencyController, coordinator::sync_pool_coordinator, rng::task_seed,
+    watchdog::Heartbeat, CopyBackDedup, EntityHandle, EntityPool, Fence, PoolCoordinator,
+    PooledTaskFences, PooledTaskRejected, ProgressReporter, ResultValidators, ScratchRng,
+};
+use bevy::{
+    app::{App, Plugin, PreUpdate},
+    ecs::{
+        component::{ComponentId, Tick},
+        entity::{Entity, EntityHashMap},
+        event::Event,
+        reflect::AppTypeRegistry,
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::Resource,
+        world::{Mut, World},
+    },
+    log::{debug, error},
+    scene::DynamicSceneBuilder,
+    tasks::{block_on, poll_once, AsyncComputeTaskPool, Task},
+    utils::{synccell::SyncCell, HashMap},
+};
+
+/// Runs pooled generation tasks and merges their results back into the main world.
+///
+/// Expects an [`EntityPool`] resource to be inserted by the app. Every frame the plugin
+/// - polls tasks spawned through [`spawn_pooled_task`] or [`PooledTasks::spawn`] and copies the
+///   components of their checked out entities from the scratch world onto the reserved main-world
+///   entities using bevy_scene, sending [`PooledTaskFinished`] once merged - or
+///   [`PooledTaskRejected`] if the results failed [`PooledTasks::validators`]. Identical slots
+///   can be deduplicated on the way, see [`PooledTasks::set_copy_back_dedup`]
+/// - copies in and starts tasks queued with [`PooledTasks::spawn_staged`] once their fences are
+///   signaled
+/// - holds back tasks over the limit set with [`EntityPool::set_task_concurrency`], adapting it to
+///   pool pressure
+/// - adds entities reserved through [`EntityPool::reserve`], reclaims entities whose handles were
+///   dropped and samples the pool statistics
+/// - mirrors cleared slots into the scratch worlds of a [`PoolCoordinator`] resource, if any
+///
+/// Only components registered in the [`AppTypeRegistry`] with `#[reflect(Component)]` are merged.
+/// Requires bevy's `multi-threaded` feature - single threaded task pools don't return task output.
+pub struct PooledTaskPlugin;
+
+impl Plugin for PooledTaskPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PooledTasks>()
+            .add_event::<PooledTaskFinished>()
+            .add_event::<PooledTaskRejected>()
+            .add_systems(
+                PreUpdate,
+                (
+                    poll_pooled_tasks,
+                    maintain_entity_pool,
+                    sync_pool_coordinator.run_if(resource_exists::<PoolCoordinator>),
+                )
+                    .chain()
+                    .run_if(resource_exists::<EntityPool>),
+            );
+    }
+}
+
+/// Identifies a task spawned through [`PooledTasks::spawn`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PooledTaskId(pub(crate) u64);
+
+/// Sent once a pooled task completed and its results were merged into the main world.
+#[derive(Event, Clone, Debug)]
+pub struct PooledTaskFinished {
+    pub id: PooledTaskId,
+    /// Main-world entities the results were merged onto.
+    pub entities: Vec<Entity>,
+    /// Seed of the task's [`ScratchRng`] - pass it to [`PooledTasks::spawn_with_seed`] to
+    /// reproduce the result.
+    pub seed: u64,
+}
+
+/// State handed to a pooled task.
+pub struct ScratchContext {
+    /// Fresh scratch world. The checked out entities are spawned with the same ids they have in
+    /// the main world, so results written to them can be copied back without remapping.
+    pub world: World,
+    /// Generator seeded with [`ScratchContext::seed`]. Draw all randomness from it to keep
+    /// results reproducible.
+    pub rng: ScratchRng,
+    /// Report progress through it to keep the watchdog from flagging the task as stuck.
+    pub progress: ProgressReporter,
+    entities: Vec<Entity>,
+    read_only: Vec<Entity>,
+    seed: u64,
+}
+
+impl ScratchContext {
+    pub(crate) fn new(world: World, entities: Vec<Entity>, seed: u64) -> Self {
+        Self {
+            world,
+            rng: ScratchRng::new(seed),
+            progress: ProgressReporter::default(),
+            entities,
+            read_only: Vec::new(),
+            seed,
+        }
+    }
+
+    /// Entities checked out for this task - only these are copied back into the main world.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Entities published by an earlier stage with [`EntityPool::publish`] - they're copied in but
+    /// never copied back, so treat them as inputs.
+    pub fn read_only(&self) -> &[Entity] {
+        &self.read_only
+    }
+
+    /// Seed [`ScratchContext::rng`] started from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+type BoxedPooledTask = Box<dyn FnOnce(&mut ScratchContext) + Send>;
+
+enum JobState {
+    /// Waiting for fences before copy-in.
+    Queued {
+        wait_for: Vec<Fence>,
+        /// Number of entities to check out on start for fresh tasks held back by the concurrency
+        /// limit, `None` for staged tasks copying in the entities they already hold.
+        checkout: Option<usize>,
+        task: SyncCell<Option<BoxedPooledTask>>,
+    },
+    Running(Task<ScratchContext>),
+}
+
+pub(crate) struct PooledJob {
+    pub(crate) id: PooledTaskId,
+    handles: Vec<EntityHandle>,
+    fences: PooledTaskFences,
+    pub(crate) seed: u64,
+    published: Vec<PublishedSlot>,
+    /// Scratch world tick right after copy-in, published slots must not change after it.
+    copied_in_at: Tick,
+    pub(crate) progress: ProgressReporter,
+    /// Watchdog state, reset when the task starts running.
+    pub(crate) heartbeat: Option<Heartbeat>,
+    state: JobState,
+}
+
+struct PublishedSlot {
+    entity: Entity,
+    components: usize,
+}
+
+struct FinishedJob {
+    handles: Vec<EntityHandle>,
+    fences: PooledTaskFences,
+    seed: u64,
+}
+
+/// Bookkeeping for in-flight pooled tasks.
+///
+/// Handles of finished tasks are kept until taken with [`PooledTasks::take_handles`] - dropping them
+/// returns the merged entities to the pool.
+#[derive(Resource, Default)]
+pub struct PooledTasks {
+    next_id: u64,
+    seed: u64,
+    pub(crate) jobs: Vec<PooledJob>,
+    finished: HashMap<PooledTaskId, FinishedJob>,
+    validators: ResultValidators,
+    dedup: Option<CopyBackDedup>,
+    concurrency: ConcurrencyController,
+}
+
+impl PooledTasks {
+    /// Checks out `count` entities from `pool` and runs `task` on the [`AsyncComputeTaskPool`] with
+    /// a fresh scratch world containing them.
+    ///
+    /// The task's [`ScratchRng`] is seeded from the task id and [`PooledTasks::seed`].
+    ///
+    /// Tasks over the concurrency limit, or spawned while others are held back, are queued without
+    /// entities and check them out when they start - in spawn order, once the pool can provide
+    /// `count` entities.
+    ///
+    /// # Panics
+    /// Panics on pool exhaustion if the task starts right away.
+    pub fn spawn<F>(
+        &mut self,
+        pool: &mut EntityPool,
+        type_registry: &AppTypeRegistry,
+        count: usize,
+        task: F,
+    ) -> PooledTaskId
+    where
+        F: FnOnce(&mut ScratchContext) + Send + 'static,
+    {
+        let seed = task_seed(self.seed, PooledTaskId(self.next_id));
+        self.spawn_with_seed(pool, type_registry, count, seed, task)
+    }
+
+    /// Like [`PooledTasks::spawn`], but seeds the task's [`ScratchRng`] with `seed` as is - use it
+    /// to reproduce a task from the seed reported by [`PooledTaskFinished`].
+    ///
+    /// # Panics
+    /// Panics on pool exhaustion if the task starts right away.
+    pub fn spawn_with_seed<F>(
+        &mut self,
+        pool: &mut EntityPool,
+        type_registry: &AppTypeRegistry,
+        count: usize,
+        seed: u64,
+        task: F,
+    ) -> PooledTaskId
+    where
+        F: FnOnce(&mut ScratchContext) + Send + 'static,
+    {
+        let throttled = self
+            .concurrency
+            .limit()
+            .is_some_and(|limit| self.running() >= limit);
+        // entities are only checked out on start, so held back tasks don't add to pool pressure
+        if throttled || self.waiting_for_checkout() {
+            let state = JobState::Queued {
+                wait_for: Vec::new(),
+                checkout: Some(count),
+                task: SyncCell::new(Some(Box::new(task))),
+            };
+            let progress = ProgressReporter::default();
+            return self.push(
+                Vec::new(),
+                PooledTaskFences::default(),
+                seed,
+                progress,
+                state,
+            );
+        }
+
+        let handles: Vec<EntityHandle> = (0..count).map(|_| pool.get()).collect();
+        let entities: Vec<Entity> = handles.iter().map(EntityHandle::entity).collect();
+
+        let fences = PooledTaskFences::default();
+        // nothing to copy in, the entities were just checked out
+        fences.copied_in.signal();
+        let world = scratch_world(type_registry, &entities);
+        let context = ScratchContext::new(world, entities, seed);
+        let progress = context.progress.clone();
+        let task = run_in_scratch(context, task);
+
+        self.push(handles, fences, seed, progress, JobState::Running(task))
+    }
+
+    /// Queues `task` on entities that are already checked out, e.g. the handles of a previous stage
+    /// taken with [`PooledTasks::take_handles`].
+    ///
+    /// Once every fence in `wait_for` is signaled, the entities' current main-world components are
+    /// copied into a fresh scratch world (copy-in) and the task is started. Waiting on the
+    /// `copied_back` fence of the task producing the data a stage reads lets stage N's copy-back
+    /// overlap with stage N+1's execution without racing on pool slots.
+    pub fn spawn_staged<F>(
+        &mut self,
+        handles: Vec<EntityHandle>,
+        wait_for: Vec<Fence>,
+        task: F,
+    ) -> PooledTaskId
+    where
+        F: FnOnce(&mut ScratchContext) + Send + 'static,
+    {
+        let state = JobState::Queued {
+            wait_for,
+            checkout: None,
+            task: SyncCell::new(Some(Box::new(task))),
+        };
+        let seed = task_seed(self.seed, PooledTaskId(self.next_id));
+        let progress = ProgressReporter::default();
+        self.push(handles, PooledTaskFences::default(), seed, progress, state)
+    }
+
+    /// User seed task seeds are derived from, `0` by default.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Sets the user seed for tasks spawned from now on.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Number of tasks currently executing, excluding queued ones.
+    pub fn running(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|job| matches!(job.state, JobState::Running(_)))
+            .count()
+    }
+
+    /// Maximum number of tasks executing at once, derived from the pool's
+    /// [`TaskConcurrency`](crate::TaskConcurrency). `None` if unlimited.
+    pub fn concurrency_limit(&self) -> Option<usize> {
+        self.concurrency.limit()
+    }
+
+    /// Progress reported by a task that is in flight.
+    pub fn progress(&self, id: PooledTaskId) -> Option<&ProgressReporter> {
+        self.jobs
+            .iter()
+            .find(|job| job.id == id)
+            .map(|job| &job.progress)
+    }
+
+    /// Cancels a task that is in flight, returning `false` if there's none with `id`.
+    ///
+    /// Its results are discarded and its handles dropped, returning the entities to the pool. The
+    /// `copied_back` fence is signaled so later stages don't wait forever. A task that already
+    /// started keeps running on its thread until it returns or checks
+    /// [`ProgressReporter::is_cancelled`].
+    pub fn cancel(&mut self, id: PooledTaskId) -> bool {
+        let Some(index) = self.jobs.iter().position(|job| job.id == id) else {
+            return false;
+        };
+
+        // keeps queued tasks in spawn order
+        let job = self.jobs.remove(index);
+        job.progress.cancel();
+        job.fences.copied_back.signal();
+        true
+    }
+
+    /// Cancels every in flight task holding entities of `pool`, see [`PooledTasks::cancel`].
+    pub(crate) fn cancel_owned_by(&mut self, pool: &EntityPool) {
+        let ids: Vec<PooledTaskId> = self
+            .jobs
+            .iter()
+            .filter(|job| job.handles.iter().any(|handle| pool.owns(handle)))
+            .map(|job| job.id)
+            .collect();
+        for id in ids {
+            self.cancel(id);
+        }
+    }
+
+    /// Checks run over results before copy-back.
+    pub fn validators(&self) -> &ResultValidators {
+        &self.validators
+    }
+
+    pub fn validators_mut(&mut self) -> &mut ResultValidators {
+        &mut self.validators
+    }
+
+    /// Enables content deduplication of copied back slots, `None` commits every slot as is.
+    pub fn set_copy_back_dedup(&mut self, dedup: Option<CopyBackDedup>) {
+        self.dedup = dedup;
+    }
+
+    pub fn copy_back_dedup(&self) -> Option<&CopyBackDedup> {
+        self.dedup.as_ref()
+    }
+
+    /// Seed of a task that is in flight or whose handles haven't been taken yet.
+    pub fn task_seed(&self, id: PooledTaskId) -> Option<u64> {
+        self.jobs
+            .iter()
+            .find(|job| job.id == id)
+            .map(|job| job.seed)
+            .or_else(|| self.finished.get(&id).map(|job| job.seed))
+    }
+
+    /// Returns `true` while the task hasn't been merged into the main world yet.
+    pub fn is_running(&self, id: PooledTaskId) -> bool {
+        self.jobs.iter().any(|job| job.id == id)
+    }
+
+    /// Fences of a task that is in flight or whose handles haven't been taken yet.
+    pub fn fences(&self, id: PooledTaskId) -> Option<&PooledTaskFences> {
+        self.jobs
+            .iter()
+            .find(|job| job.id == id)
+            .map(|job| &job.fences)
+            .or_else(|| self.finished.get(&id).map(|job| &job.fences))
+    }
+
+    /// Takes ownership of the handles of a finished task.
+    pub fn take_handles(&mut self, id: PooledTaskId) -> Option<Vec<EntityHandle>> {
+        self.finished.remove(&id).map(|job| job.handles)
+    }
+
+    /// Returns `true` if a fresh task is held back, new ones queue up behind it.
+    fn waiting_for_checkout(&self) -> bool {
+        self.jobs.iter().any(|job| {
+            matches!(
+                job.state,
+                JobState::Queued {
+                    checkout: Some(_),
+                    ..
+                }
+            )
+        })
+    }
+
+    fn push(
+        &mut self,
+        handles: Vec<EntityHandle>,
+        fences: PooledTaskFences,
+        seed: u64,
+        progress: ProgressReporter,
+        state: JobState,
+    ) -> PooledTaskId {
+        let id = PooledTaskId(self.next_id);
+        self.next_id += 1;
+        let heartbeat = matches!(state, JobState::Running(_)).then(Heartbeat::default);
+        self.jobs.push(PooledJob {
+            id,
+            handles,
+            fences,
+            seed,
+            published: Vec::new(),
+            copied_in_at: Tick::new(0),
+            progress,
+            heartbeat,
+            state,
+        });
+
+        id
+    }
+}
+
+/// Convenience wrapper around [`PooledTasks::spawn`] for exclusive systems.
+///
+/// # Panics
+/// Panics if [`PooledTaskPlugin`] isn't added, there's no [`EntityPool`] resource or on pool
+/// exhaustion for a task starting right away.
+pub fn spawn_pooled_task<F>(world: &mut World, count: usize, task: F) -> PooledTaskId
+where
+    F: FnOnce(&mut ScratchContext) + Send + 'static,
+{
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    world.resource_scope(|world, mut tasks: Mut<PooledTasks>| {
+        world.resource_scope(|world, mut pool: Mut<EntityPool>| {
+            pool.flush(world);
+            tasks.spawn(&mut pool, &type_registry, count, task)
+        })
+    })
+}
+
+/// Merges the results of completed pooled tasks into the main world, then copies in and starts
+/// staged tasks whose fences were signaled.
+pub fn poll_pooled_tasks(world: &mut World) {
+    world.resource_scope(|world, mut tasks: Mut<PooledTasks>| {
+        let mut index = 0;
+        while index < tasks.jobs.len() {
+            let JobState::Running(task) = &mut tasks.jobs[index].state else {
+                index += 1;
+                continue;
+            };
+            let Some(context) = block_on(poll_once(task)) else {
+                index += 1;
+                continue;
+            };
+
+            let PooledJob {
+                id,
+                handles,
+                fences,
+                seed,
+                published,
+                copied_in_at,
+                ..
+            } = tasks.jobs.remove(index);
+            fences.executed.signal();
+            if cfg!(debug_assertions) {
+                check_published(id, &context, &published, copied_in_at);
+            }
+
+            let report = tasks
+                .validators
+                .validate(id, &context.world, copied_back(&context));
+            if report.is_ok() {
+                merge_scratch(id, &context, world, tasks.dedup.as_mut());
+                debug!("pooled task {id:?} with seed {seed:#018x} finished");
+                world.send_event(PooledTaskFinished {
+                    id,
+                    entities: context.entities,
+                    seed,
+                });
+            } else {
+                error!("{report} (seed {seed:#018x})");
+                world.send_event(PooledTaskRejected { id, seed, report });
+            }
+            // rejected tasks are done with their slots too, so later stages don't wait forever
+            fences.copied_back.signal();
+
+            tasks.finished.insert(
+                id,
+                FinishedJob {
+                    handles,
+                    fences,
+                    seed,
+                },
+            );
+        }
+
+        if let Some(pool) = world.get_resource::<EntityPool>() {
+            let stats = pool.stats();
+            tasks.concurrency.update(pool.task_concurrency(), &stats);
+        }
+        let mut running = tasks.running();
+        let limit = tasks.concurrency.limit().unwrap_or(usize::MAX);
+
+        // after merging, so stages waiting on this frame's copy-backs start right away
+        let mut starved = false;
+        for job in &mut tasks.jobs {
+            if running >= limit {
+                break;
+            }
+            let JobState::Queued {
+                wait_for,
+                checkout,
+                task,
+            } = &mut job.state
+            else {
+                continue;
+            };
+            if !wait_for.iter().all(Fence::is_signaled) {
+                continue;
+            }
+            let checkout = *checkout;
+            if let Some(count) = checkout {
+                // fresh tasks start in spawn order, staged ones still run and free their entities
+                if starved {
+                    continue;
+                }
+                let Some(mut pool) = world.get_resource_mut::<EntityPool>() else {
+                    continue;
+                };
+                if pool.capacity() - pool.stats().in_use < count {
+                    starved = true;
+                    continue;
+                }
+                job.handles = (0..count).map(|_| pool.get()).collect();
+            }
+            let Some(task) = task.get().take() else {
+                continue;
+            };
+            running += 1;
+
+            let entities: Vec<Entity> = job.handles.iter().map(EntityHandle::entity).collect();
+            let read_only: Vec<Entity> = world
+                .get_resource::<EntityPool>()
+                .map(|pool| {
+                    job.handles
+                        .iter()
+                        .filter(|handle| pool.is_published(handle.key()))
+                        .map(EntityHandle::entity)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let mut scratch = if checkout.is_none() {
+                copy_in(world, &entities)
+            } else {
+                scratch_world(world.resource::<AppTypeRegistry>(), &entities)
+            };
+            job.copied_in_at = scratch.change_tick();
+            // later writes get a newer tick than the copied in components
+            scratch.increment_change_tick();
+            job.published = read_only
+                .iter()
+                .map(|&entity| PublishedSlot {
+                    entity,
+                    components: scratch.entity(entity).archetype().components().count(),
+                })
+                .collect();
+            job.fences.copied_in.signal();
+
+            let mut context = ScratchContext::new(scratch, entities, job.seed);
+            context.read_only = read_only;
+            context.progress = job.progress.clone();
+            job.heartbeat = Some(Heartbeat::default());
+            job.state = JobState::Running(run_in_scratch(context, task));
+        }
+    });
+}
+
+/// Flushes pending reservations, reclaims released entities and samples pool statistics.
+pub fn maintain_entity_pool(world: &mut World) {
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| {
+        pool.flush(world);
+        pool.reclaim(world);
+        pool.sample_stats();
+    });
+}
+
+pub(crate) fn scratch_world(type_registry: &AppTypeRegistry, entities: &[Entity]) -> World {
+    let mut world = World::new();
+    // scene extraction reads the registry from the world it extracts from
+    world.insert_resource(type_registry.clone());
+    if let Err(e) = world.insert_or_spawn_batch(entities.iter().copied().map(|e| (e, ()))) {
+        panic!("Failed to spawn pooled entities in scratch world {e:?}");
+    }
+    world
+}
+
+fn run_in_scratch(
+    mut context: ScratchContext,
+    task: impl FnOnce(&mut ScratchContext) + Send + 'static,
+) -> Task<ScratchContext> {
+    AsyncComputeTaskPool::get().spawn(async move {
+        task(&mut context);
+        context
+    })
+}
+
+/// Builds a scratch world holding the current main-world components of `entities`.
+fn copy_in(world: &World, entities: &[Entity]) -> World {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let mut scratch = scratch_world(&type_registry, entities);
+
+    let scene = DynamicSceneBuilder::from_world(world)
+        .extract_entities(entities.iter().copied())
+        .build();
+    let mut entity_map: EntityHashMap<Entity> = entities.iter().map(|&e| (e, e)).collect();
+    if let Err(e) = scene.write_to_world(&mut scratch, &mut entity_map) {
+        error!("Failed to copy pooled entities into scratch world: {e}");
+    }
+    scratch
+}
+
+/// Panics if the task changed a slot published by an earlier stage, see [`EntityPool::publish`].
+fn check_published(
+    id: PooledTaskId,
+    context: &ScratchContext,
+    published: &[PublishedSlot],
+    copied_in_at: Tick,
+) {
+    let this_run = context.world.read_change_tick();
+    for slot in published {
+        let Some(entity) = context.world.get_entity(slot.entity) else {
+            panic!(
+                "pooled task {id:?} despawned read-only slot {:?}",
+                slot.entity
+            );
+        };
+        let components: Vec<ComponentId> = entity.archetype().components().collect();
+        let changed = components.len() != slot.components
+            || components.iter().any(|&component| {
+                entity
+                    .get_change_ticks_by_id(component)
+                    .is_some_and(|ticks| ticks.is_changed(copied_in_at, this_run))
+            });
+        assert!(
+            !changed,
+            "pooled task {id:?} changed read-only slot {:?} published by an earlier stage",
+            slot.entity
+        );
+    }
+}
+
+/// Entities whose components are copied back - published slots are inputs only.
+fn copied_back(context: &ScratchContext) -> impl Iterator<Item = Entity> + '_ {
+    context
+        .entities
+        .iter()
+        .copied()
+        .filter(|entity| !context.read_only.contains(entity))
+}
+
+fn merge_scratch(
+    id: PooledTaskId,
+    context: &ScratchContext,
+    world: &mut World,
+    dedup: Option<&mut CopyBackDedup>,
+) {
+    let mut scene = DynamicSceneBuilder::from_world(&context.world)
+        .extract_entities(copied_back(context))
+        .build();
+    let duplicates = dedup.as_ref().map(|dedup| {
+        let (unique, duplicates) = dedup.split(id, std::mem::take(&mut scene.entities));
+        scene.entities = unique;
+        duplicates
+    });
+
+    // scratch entities share ids with the reserved main-world entities
+    let mut entity_map: EntityHashMap<Entity> = context.entities.iter().map(|&e| (e, e)).collect();
+    if let Err(e) = scene.write_to_world(world, &mut entity_map) {
+        error!("Failed to merge pooled task results: {e}");
+    }
+
+    if let (Some(dedup), Some(duplicates)) = (dedup, duplicates) {
+        if !duplicates.is_empty() {
+            debug!(
+                "pooled task {id:?} skipped {} duplicate slots",
+                duplicates.len()
+            );
+        }
+        dedup.resolve(world, duplicates);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::EntityPool;
+use bevy::{
+    app::{App, Plugin, PreStartup, Update},
+    asset::{AssetServer, Assets, Handle},
+    ecs::{
+        entity::Entity,
+        reflect::{AppTypeRegistry, ReflectComponent},
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::Resource,
+        world::{Mut, World},
+    },
+    log::warn,
+    reflect::Reflect,
+    scene::DynamicScene,
+};
+
+/// Initial component layout of pool slots, taken from a [`DynamicScene`].
+///
+/// With a single template entity every slot gets its components, with several slot `i` gets entity
+/// `i % n`'s - e.g. to alternate layouts across the pool.
+pub struct SlotTemplate {
+    layouts: Vec<Vec<Box<dyn Reflect>>>,
+}
+
+impl SlotTemplate {
+    /// Copies the components of `scene`'s entities, resources are ignored.
+    pub fn from_scene(scene: &DynamicScene) -> Self {
+        Self {
+            layouts: scene
+                .entities
+                .iter()
+                .map(|entity| {
+                    entity
+                        .components
+                        .iter()
+                        .map(|component| component.clone_value())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Number of template entities.
+    pub fn len(&self) -> usize {
+        self.layouts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layouts.is_empty()
+    }
+}
+
+impl Clone for SlotTemplate {
+    fn clone(&self) -> Self {
+        Self {
+            layouts: self
+                .layouts
+                .iter()
+                .map(|layout| layout.iter().map(|c| c.clone_value()).collect())
+                .collect(),
+        }
+    }
+}
+
+impl EntityPool {
+    /// Gives every slot `template`'s component layout - free slots right away, in use slots once
+    /// released, and every slot is reset to it from then on instead of being cleared.
+    ///
+    /// Template components must be registered in the world's [`AppTypeRegistry`] with
+    /// `#[reflect(Component)]`, unregistered ones are skipped with a warning.
+    pub fn set_template(&mut self, template: SlotTemplate, world: &mut World) {
+        self.debug_assert_world(world);
+
+        self.template = Some(template);
+        for slot in 0..self.entities.len() as u32 {
+            if !self.in_use[slot as usize] {
+                self.apply_template(slot, world);
+            }
+        }
+    }
+
+    /// Stops resetting released slots to the template. Slots keep their current components.
+    pub fn clear_template(&mut self) -> Option<SlotTemplate> {
+        self.template.take()
+    }
+
+    pub fn template(&self) -> Option<&SlotTemplate> {
+        self.template.as_ref()
+    }
+
+    pub(crate) fn apply_template(&self, slot: u32, world: &mut World) {
+        self.apply_template_to(slot, self.entities[slot as usize], world);
+    }
+
+    /// Applies the layout of `slot` to `entity`, e.g. the slot's entity in another world.
+    pub(crate) fn apply_template_to(&self, slot: u32, entity: Entity, world: &mut World) {
+        let Some(template) = &self.template else {
+            return;
+        };
+        if template.is_empty() {
+            return;
+        }
+
+        let Some(type_registry) = world.get_resource::<AppTypeRegistry>().cloned() else {
+            warn!("can't apply slot template without an AppTypeRegistry");
+            return;
+        };
+        let type_registry = type_registry.read();
+        let layout = &template.layouts[slot as usize % template.layouts.len()];
+        let mut entity = world.entity_mut(entity);
+        for component in layout {
+            let Some(reflect_component) = component
+                .get_represented_type_info()
+                .and_then(|info| type_registry.get_type_data::<ReflectComponent>(info.type_id()))
+            else {
+                warn!(
+                    "slot template component {} isn't registered as a component",
+                    component.reflect_type_path()
+                );
+                continue;
+            };
+            reflect_component.apply_or_insert(&mut entity, &**component, &type_registry);
+        }
+    }
+}
+
+/// Loads a scene asset at startup and applies it as the [`SlotTemplate`] of the app's
+/// [`EntityPool`] resource once it finished loading - lets data-driven projects configure the
+/// scratch slot shape without code changes.
+///
+/// Requires bevy's `AssetPlugin` and the scene asset loader.
+pub struct PoolTemplatePlugin {
+    /// Asset path of the scene, e.g. `"pool_slots.scn.ron"`.
+    pub scene: String,
+}
+
+impl PoolTemplatePlugin {
+    pub fn new(scene: impl Into<String>) -> Self {
+        Self {
+            scene: scene.into(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct PendingTemplate(Handle<DynamicScene>);
+
+impl Plugin for PoolTemplatePlugin {
+    fn build(&self, app: &mut App) {
+        let scene = self.scene.clone();
+        app.add_systems(PreStartup, move |world: &mut World| {
+            let handle = world.resource::<AssetServer>().load(scene.clone());
+            world.insert_resource(PendingTemplate(handle));
+        })
+        .add_systems(
+            Update,
+            apply_pool_template
+                .run_if(resource_exists::<PendingTemplate>)
+                .run_if(resource_exists::<EntityPool>),
+        );
+    }
+}
+
+/// Applies the template once its scene loaded, then stops running.
+fn apply_pool_template(world: &mut World) {
+    let handle = &world.resource::<PendingTemplate>().0;
+    let Some(scene) = world.resource::<Assets<DynamicScene>>().get(handle) else {
+        return;
+    };
+
+    let template = SlotTemplate::from_scene(scene);
+    world.remove_resource::<PendingTemplate>();
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| pool.set_template(template, world));
+}
@@ -0,0 +1,129 @@
+use crate::PooledTaskId;
+use bevy::ecs::{
+    component::Component,
+    entity::Entity,
+    event::Event,
+    world::{EntityRef, World},
+};
+use std::{borrow::Cow, fmt};
+
+type SlotValidator = Box<dyn Fn(EntityRef) -> Result<(), String> + Send + Sync>;
+
+/// Checks run over a pooled task's results in its scratch world before they are copied back.
+///
+/// A task with a failing check is rejected - nothing is merged into the main world and
+/// [`PooledTaskRejected`] is sent instead of [`PooledTaskFinished`](crate::PooledTaskFinished).
+#[derive(Default)]
+pub struct ResultValidators {
+    validators: Vec<(Cow<'static, str>, SlotValidator)>,
+}
+
+impl ResultValidators {
+    /// Validates every `T` on a copied back entity, e.g. that a `Transform` is finite.
+    pub fn add_component<T: Component>(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        validator: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.add_slot(name, move |entity| {
+            entity.get::<T>().map_or(Ok(()), &validator)
+        })
+    }
+
+    /// Validates every copied back entity as a whole, for invariants spanning components.
+    pub fn add_slot(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        validator: impl Fn(EntityRef) -> Result<(), String> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.validators.push((name.into(), Box::new(validator)));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    /// Runs every validator over `entities` in `world`. Despawned entities are skipped.
+    pub fn validate(
+        &self,
+        task: PooledTaskId,
+        world: &World,
+        entities: impl IntoIterator<Item = Entity>,
+    ) -> ValidationReport {
+        let mut report = ValidationReport {
+            task,
+            failures: Vec::new(),
+        };
+        if self.validators.is_empty() {
+            return report;
+        }
+
+        for entity in entities {
+            let Some(entity_ref) = world.get_entity(entity) else {
+                continue;
+            };
+            for (name, validator) in &self.validators {
+                if let Err(message) = validator(entity_ref) {
+                    report.failures.push(ValidationFailure {
+                        entity,
+                        validator: name.clone(),
+                        message,
+                    });
+                }
+            }
+        }
+        report
+    }
+}
+
+/// A single failed check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationFailure {
+    /// Slot entity the check failed on.
+    pub entity: Entity,
+    /// Name the validator was registered with.
+    pub validator: Cow<'static, str>,
+    pub message: String,
+}
+
+/// Every failed check of a task's results.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub task: PooledTaskId,
+    pub failures: Vec<ValidationFailure>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pooled task {:?} failed {} checks",
+            self.task,
+            self.failures.len()
+        )?;
+        for failure in &self.failures {
+            write!(
+                f,
+                "\n- {:?} {}: {}",
+                failure.entity, failure.validator, failure.message
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Sent instead of [`PooledTaskFinished`](crate::PooledTaskFinished) when a task's results failed
+/// validation. Nothing was merged into the main world, the task's handles can still be taken.
+#[derive(Event, Clone, Debug)]
+pub struct PooledTaskRejected {
+    pub id: PooledTaskId,
+    pub seed: u64,
+    pub report: ValidationReport,
+}
@@ -0,0 +1,136 @@
+use crate::{poll_pooled_tasks, PooledTaskId, PooledTasks};
+use bevy::{
+    app::{App, Plugin, PreUpdate},
+    ecs::{
+        event::{Event, EventWriter},
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Res, ResMut, Resource},
+    },
+    log::warn,
+    utils::{Duration, Instant},
+};
+
+/// Last heartbeat the watchdog saw from a running task.
+pub(crate) struct Heartbeat {
+    beats: u64,
+    at: Instant,
+    flagged: bool,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self {
+            beats: 0,
+            at: Instant::now(),
+            flagged: false,
+        }
+    }
+}
+
+/// Sent once per task when it reported no progress within [`PooledTaskWatchdog::window`].
+#[derive(Event, Clone, Debug)]
+pub struct PooledTaskStalled {
+    pub id: PooledTaskId,
+    pub seed: u64,
+    /// Time since the last heartbeat, or since the task started if it never reported.
+    pub stalled_for: Duration,
+    /// Last reported progress fraction.
+    pub progress: f32,
+    /// `true` if the task was cancelled, see [`PooledTaskWatchdog::cancel_stalled`].
+    pub cancelled: bool,
+}
+
+/// Watchdog settings, inserted by [`PooledTaskWatchdogPlugin`].
+#[derive(Resource, Clone, Debug)]
+pub struct PooledTaskWatchdog {
+    /// How long a running task may go without a heartbeat before it's flagged.
+    pub window: Duration,
+    /// Cancels flagged tasks with [`PooledTasks::cancel`], returning their slots to the pool.
+    pub cancel_stalled: bool,
+}
+
+impl Default for PooledTaskWatchdog {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(10),
+            cancel_stalled: false,
+        }
+    }
+}
+
+/// Flags running pooled tasks that report no progress through their
+/// [`ProgressReporter`](crate::ProgressReporter) within a configurable window, sending
+/// [`PooledTaskStalled`] and optionally cancelling them - a task hanging on malformed input would
+/// otherwise hold its slots forever.
+///
+/// Tasks that never report are flagged once they ran longer than the window. Requires
+/// [`PooledTaskPlugin`](crate::PooledTaskPlugin).
+#[derive(Default)]
+pub struct PooledTaskWatchdogPlugin {
+    pub settings: PooledTaskWatchdog,
+}
+
+impl Plugin for PooledTaskWatchdogPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_event::<PooledTaskStalled>()
+            .add_systems(
+                PreUpdate,
+                watch_pooled_tasks
+                    .before(poll_pooled_tasks)
+                    .run_if(resource_exists::<PooledTasks>),
+            );
+    }
+}
+
+/// Flags and optionally cancels stalled tasks, see [`PooledTaskWatchdogPlugin`].
+pub fn watch_pooled_tasks(
+    mut tasks: ResMut<PooledTasks>,
+    watchdog: Res<PooledTaskWatchdog>,
+    mut stalled: EventWriter<PooledTaskStalled>,
+) {
+    let now = Instant::now();
+    let mut flagged = Vec::new();
+    for job in &mut tasks.jobs {
+        let Some(heartbeat) = &mut job.heartbeat else {
+            continue;
+        };
+
+        let beats = job.progress.beats();
+        if beats != heartbeat.beats {
+            *heartbeat = Heartbeat {
+                beats,
+                at: now,
+                flagged: false,
+            };
+            continue;
+        }
+
+        let stalled_for = now.duration_since(heartbeat.at);
+        if heartbeat.flagged || stalled_for < watchdog.window {
+            continue;
+        }
+        heartbeat.flagged = true;
+        flagged.push(PooledTaskStalled {
+            id: job.id,
+            seed: job.seed,
+            stalled_for,
+            progress: job.progress.progress(),
+            cancelled: watchdog.cancel_stalled,
+        });
+    }
+
+    for event in flagged {
+        warn!(
+            "pooled task {:?} (seed {:#018x}) made no progress for {:?}{}",
+            event.id,
+            event.seed,
+            event.stalled_for,
+            if event.cancelled { ", cancelling" } else { "" }
+        );
+        if event.cancelled {
+            tasks.cancel(event.id);
+        }
+        stalled.send(event);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{stats::occupancy, EntityPool, PoolId};
+use bevy::{
+    app::{App, Last, Plugin},
+    ecs::{
+        event::{Event, EventWriter},
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Local, Res},
+    },
+};
+
+/// An occupancy threshold was crossed.
+///
+/// Passed to callbacks registered with [`EntityPool::on_watermark`] the moment it happens, and sent
+/// as an event by [`PoolWatermarkPlugin`] once per frame.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct WatermarkCrossed {
+    pub pool: PoolId,
+    /// Threshold as a fraction of capacity, in `0.0..=1.0`.
+    pub threshold: f32,
+    /// Occupancy right after the crossing.
+    pub occupancy: f32,
+    /// `true` if occupancy rose to or above the threshold, `false` if it fell below again.
+    pub rising: bool,
+}
+
+type WatermarkCallback = Box<dyn FnMut(&WatermarkCrossed) + Send + Sync>;
+
+struct Watermark {
+    threshold: f32,
+    reached: bool,
+    callback: WatermarkCallback,
+}
+
+#[derive(Default)]
+pub(crate) struct Watermarks(Vec<Watermark>);
+
+impl Watermarks {
+    pub(crate) fn update(&mut self, pool: PoolId, in_use: usize, capacity: usize) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        let occupancy = occupancy(in_use, capacity);
+        for watermark in &mut self.0 {
+            let reached = occupancy >= watermark.threshold;
+            if reached != watermark.reached {
+                watermark.reached = reached;
+                (watermark.callback)(&WatermarkCrossed {
+                    pool,
+                    threshold: watermark.threshold,
+                    occupancy,
+                    rising: reached,
+                });
+            }
+        }
+    }
+}
+
+impl EntityPool {
+    /// Calls `callback` whenever occupancy crosses `threshold` (a fraction of capacity, e.g. `0.9`),
+    /// in either direction - use it to throttle background generation before the pool is exhausted.
+    ///
+    /// # Panics
+    /// Panics if `threshold` is not in `0.0..=1.0`.
+    pub fn on_watermark(
+        &mut self,
+        threshold: f32,
+        callback: impl FnMut(&WatermarkCrossed) + Send + Sync + 'static,
+    ) {
+        assert_threshold(threshold);
+
+        let reached = occupancy(self.in_use_count(), self.entities.len()) >= threshold;
+        self.watermarks.0.push(Watermark {
+            threshold,
+            reached,
+            callback: Box::new(callback),
+        });
+    }
+}
+
+fn assert_threshold(threshold: f32) {
+    assert!(
+        (0.0..=1.0).contains(&threshold),
+        "watermark threshold must be in 0..=1, got {threshold}"
+    );
+}
+
+/// Sends [`WatermarkCrossed`] events when the occupancy of the app's [`EntityPool`] resource
+/// crosses one of `thresholds` between frames.
+///
+/// Only the occupancy at the end of each frame is compared - register a callback with
+/// [`EntityPool::on_watermark`] to observe crossings within a frame.
+///
+/// # Panics
+/// Building the plugin panics if a threshold is not in `0.0..=1.0`, or if `thresholds` isn't
+/// strictly ascending.
+pub struct PoolWatermarkPlugin {
+    pub thresholds: Vec<f32>,
+}
+
+impl Default for PoolWatermarkPlugin {
+    fn default() -> Self {
+        Self {
+            thresholds: vec![0.75, 0.9, 1.0],
+        }
+    }
+}
+
+impl Plugin for PoolWatermarkPlugin {
+    fn build(&self, app: &mut App) {
+        for &threshold in &self.thresholds {
+            assert_threshold(threshold);
+        }
+        for pair in self.thresholds.windows(2) {
+            assert!(
+                pair[0] < pair[1],
+                "watermark thresholds must be strictly ascending, got {} before {}",
+                pair[0],
+                pair[1]
+            );
+        }
+        let thresholds = self.thresholds.clone();
+        app.add_event::<WatermarkCrossed>().add_systems(
+            Last,
+            (move |pool: Res<EntityPool>,
+                   reached: Local<Vec<bool>>,
+                   events: EventWriter<WatermarkCrossed>| {
+                send_watermark_events(&thresholds, pool, reached, events);
+            })
+            .run_if(resource_exists::<EntityPool>),
+        );
+    }
+}
+
+fn send_watermark_events(
+    thresholds: &[f32],
+    pool: Res<EntityPool>,
+    mut reached: Local<Vec<bool>>,
+    mut events: EventWriter<WatermarkCrossed>,
+) {
+    let stats = pool.stats();
+    let occupancy = occupancy(stats.in_use, stats.capacity);
+    // nothing counts as crossed before the first frame
+    reached.resize(thresholds.len(), false);
+
+    for (&threshold, reached) in thresholds.iter().zip(reached.iter_mut()) {
+        let now = occupancy >= threshold;
+        if now != *reached {
+            *reached = now;
+            events.send(WatermarkCrossed {
+                pool: pool.id(),
+                threshold,
+                occupancy,
+                rising: now,
+            });
+        }
+    }
+}
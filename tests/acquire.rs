@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use bevy_entity_pool::EntityPool;
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn acquisition_stops_at_exhaustion() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 3);
+    let held = pool.get();
+
+    let acquired = pool.acquire_while_available();
+    assert_eq!(acquired.len(), 2);
+    let handles: Vec<_> = acquired.collect();
+    assert_eq!(handles.len(), 2);
+    assert!(handles
+        .iter()
+        .all(|handle| handle.entity() != held.entity()));
+
+    let stats = pool.stats();
+    assert_eq!((stats.in_use, stats.failed_acquisitions), (3, 0));
+    assert_eq!(pool.acquire_while_available().count(), 0);
+    assert_eq!(pool.stats().failed_acquisitions, 0);
+}
+
+#[test]
+fn zipped_acquisition_takes_only_what_it_needs() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 4);
+    let props = ["tree", "rock"];
+
+    let placed: Vec<_> = props
+        .iter()
+        .zip(pool.acquire_while_available())
+        .map(|(_, handle)| handle)
+        .collect();
+    assert_eq!(placed.len(), 2);
+    assert_eq!(pool.stats().in_use, 2);
+}
+
+#[test]
+fn leaks_point_at_the_acquiring_call() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 2);
+    pool.set_leak_detection(true);
+
+    let line = line!() + 1;
+    let handles: Vec<_> = pool.acquire_while_available().collect();
+    pool.free_entities(&mut world);
+
+    let leaks = pool.take_leaks();
+    assert_eq!(leaks.len(), 2);
+    assert!(leaks
+        .iter()
+        .all(|leak| leak.acquired_at.line() == line && leak.acquired_at.file() == file!()));
+    drop(handles);
+}
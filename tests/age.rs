@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{EntityPool, PoolGeneration};
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn recovering_free_slots_records_no_releases() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 4);
+    let handle = pool.get();
+
+    let free: Vec<Entity> = pool
+        .entities()
+        .iter()
+        .copied()
+        .filter(|&entity| entity != handle.entity())
+        .take(2)
+        .collect();
+    for entity in free {
+        world.despawn(entity);
+    }
+    pool.recover(&mut world);
+
+    let stats = pool.generation_stats(PoolGeneration::default()).unwrap();
+    assert_eq!(
+        (stats.acquisitions, stats.releases, stats.live()),
+        (1, 0, 1)
+    );
+
+    world.despawn(handle.entity());
+    pool.recover(&mut world);
+    let stats = pool.generation_stats(PoolGeneration::default()).unwrap();
+    assert_eq!(
+        (stats.acquisitions, stats.releases, stats.live()),
+        (1, 1, 0)
+    );
+}
+
+#[test]
+fn releases_count_against_the_acquiring_generation() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 4);
+    let old = pool.get();
+    let generation = pool.next_generation();
+    let _new = pool.get();
+    assert_eq!(pool.acquired_in(old.key()), Some(PoolGeneration::default()));
+
+    pool.release(old.key(), &mut world);
+    let first = pool.generation_stats(PoolGeneration::default()).unwrap();
+    let second = pool.generation_stats(generation).unwrap();
+    assert_eq!((first.live(), second.live()), (0, 1));
+}
+
+#[test]
+#[should_panic(expected = "outlived the maximum age")]
+fn slots_outliving_the_maximum_age_panic() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 4);
+    pool.set_max_generation_age(Some(1));
+    let _handle = pool.get();
+    pool.next_generation();
+    pool.next_generation();
+}
+
+#[test]
+fn peaks_include_slots_from_earlier_generations() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 4);
+    let _old: Vec<_> = (0..3).map(|_| pool.get()).collect();
+    let generation = pool.next_generation();
+    assert_eq!(generation.get(), 1);
+    assert_eq!(pool.generation(), generation);
+
+    let _new = pool.get();
+    let first = pool.generation_stats(PoolGeneration::default()).unwrap();
+    let second = pool.generation_stats(generation).unwrap();
+    assert_eq!((first.peak_in_use, second.peak_in_use), (3, 4));
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{
+    BitmapAllocator, EntityPool, GroupedAllocator, RingAllocator, SlotAllocator, StackAllocator,
+};
+use std::ops::Range;
+
+#[test]
+fn grouped_allocator_reuses_lowest_freed_slot() {
+    let mut world = World::new();
+    let entities = world.entities().reserve_entities(4).collect();
+    let mut pool = EntityPool::with_allocator(entities, &mut world, GroupedAllocator::new(4));
+
+    let handles: Vec<_> = (0..4).map(|_| pool.get()).collect();
+    let [a, b, c, d] = handles.try_into().unwrap();
+    drop(b);
+    drop(d);
+    drop(a);
+    pool.reclaim(&mut world);
+
+    let slots: Vec<u32> = (0..3).map(|_| pool.get().key().index()).collect();
+    assert_eq!(slots, [0, 1, 3]);
+    drop(c);
+}
+
+/// Takes `0..4` while slot 2 is taken, then checks all other slots are still free.
+fn failed_range_takes_nothing(mut allocator: impl SlotAllocator) {
+    allocator.grow(0..6);
+    assert!(allocator.take(2));
+
+    assert!(!allocator.take_range(0..4));
+    assert_eq!(allocator.available(), 5);
+    for slot in [0, 1, 3, 4, 5] {
+        assert!(allocator.take(slot), "slot {slot} leaked");
+    }
+}
+
+/// Only implements the required methods, so it uses the default `take_range`.
+#[derive(Default)]
+struct DefaultRange(StackAllocator);
+
+impl SlotAllocator for DefaultRange {
+    fn grow(&mut self, slots: Range<u32>) {
+        self.0.grow(slots);
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        self.0.allocate()
+    }
+
+    fn take(&mut self, slot: u32) -> bool {
+        self.0.take(slot)
+    }
+
+    fn free(&mut self, slot: u32) {
+        self.0.free(slot);
+    }
+
+    fn available(&self) -> usize {
+        self.0.available()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+#[test]
+fn failed_ranges_take_nothing() {
+    failed_range_takes_nothing(DefaultRange::default());
+    failed_range_takes_nothing(StackAllocator::default());
+    failed_range_takes_nothing(RingAllocator::default());
+    failed_range_takes_nothing(BitmapAllocator::default());
+    failed_range_takes_nothing(GroupedAllocator::new(4));
+}
+
+#[test]
+fn ranges_past_the_end_take_nothing() {
+    let mut allocator = GroupedAllocator::new(4);
+    allocator.grow(0..4);
+    assert!(!allocator.take_range(2..6));
+    assert_eq!(allocator.available(), 4);
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{
+    bake_runner::{BakeJob, BakeOutcome, BakeRunner},
+    ScratchContext,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Tile(u32);
+
+fn tiles(value: u32) -> impl FnOnce(&mut ScratchContext) + Send + 'static {
+    move |context| {
+        for entity in context.entities().to_vec() {
+            context.world.entity_mut(entity).insert(Tile(value));
+        }
+    }
+}
+
+fn runner(capacity: usize) -> BakeRunner {
+    BakeRunner::new(capacity).seed(7).configure(|app| {
+        app.register_type::<Tile>();
+    })
+}
+
+#[test]
+fn jobs_start_in_order_and_report_in_order() {
+    let baked = Arc::new(Mutex::new(Vec::new()));
+    let on_finished = |name: &'static str| {
+        let baked = baked.clone();
+        move |world: &mut World, entities: &[Entity]| {
+            let values: Vec<u32> = entities
+                .iter()
+                .map(|&entity| world.get::<Tile>(entity).unwrap().0)
+                .collect();
+            baked.lock().unwrap().push((name, values));
+        }
+    };
+
+    let report = runner(4)
+        .job(BakeJob::new("a", 3, tiles(1)).on_finished(on_finished("a")))
+        .job(BakeJob::new("b", 3, tiles(2)).on_finished(on_finished("b")))
+        .job(BakeJob::new("c", 1, tiles(3)).on_finished(on_finished("c")))
+        .run();
+
+    assert!(report.is_success());
+    let names: Vec<&str> = report.jobs.iter().map(|job| job.name.as_str()).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+    let started: Vec<u64> = report
+        .jobs
+        .iter()
+        .map(|job| job.started_frame.unwrap())
+        .collect();
+    // b needs a's slots, c doesn't overtake b even though it would fit next to a
+    assert_eq!(started[0], 0);
+    assert!(started[1] > started[0]);
+    assert!(started[2] >= started[1]);
+    assert!(report.peak_in_use <= 4);
+    assert!(report.jobs.iter().all(|job| job.seed.is_some()));
+
+    let mut baked = baked.lock().unwrap().clone();
+    baked.sort();
+    assert_eq!(
+        baked,
+        [("a", vec![1; 3]), ("b", vec![2; 3]), ("c", vec![3])]
+    );
+}
+
+#[test]
+fn jobs_larger_than_the_pool_are_reported() {
+    let report = runner(2)
+        .job(BakeJob::new("huge", 3, tiles(1)))
+        .job(BakeJob::new("small", 2, tiles(2)))
+        .run();
+
+    assert!(!report.is_success());
+    assert_eq!(report.jobs[0].outcome, BakeOutcome::TooLarge);
+    assert_eq!(report.jobs[0].seed, None);
+    assert_eq!(report.jobs[0].started_frame, None);
+    assert_eq!(report.jobs[1].outcome, BakeOutcome::Finished);
+}
+
+#[test]
+fn reports_serialize_to_json() {
+    let report = runner(2)
+        .job(BakeJob::new("say \"hi\"\n", 1, tiles(1)))
+        .job(BakeJob::new("huge", 3, tiles(1)))
+        .run();
+    let json = report.to_json();
+
+    assert!(json.starts_with("{\"capacity\":2,"), "{json}");
+    assert!(json.contains("\"success\":false"), "{json}");
+    let seed = report.jobs[0].seed.unwrap();
+    assert!(
+        json.contains(&format!(
+            "{{\"name\":\"say \\\"hi\\\"\\n\",\"slots\":1,\"outcome\":\"finished\",\
+             \"seed\":\"{seed:#018x}\",\"started_frame\":0,"
+        )),
+        "{json}"
+    );
+    assert!(
+        json.contains(
+            "{\"name\":\"huge\",\"slots\":3,\"outcome\":\"too_large\",\"seed\":null,\
+             \"started_frame\":null,\"duration_secs\":0}"
+        ),
+        "{json}"
+    );
+    assert!(json.ends_with("]}"), "{json}");
+}
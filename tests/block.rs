@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{EntityPool, GroupedAllocator, SlotAllocator, StackAllocator};
+use std::ops::Range;
+
+fn entities(world: &mut World, count: u32) -> Vec<Entity> {
+    world.entities().reserve_entities(count).collect()
+}
+
+#[test]
+fn trees_accept_trailing_zero_entries() {
+    let mut world = World::new();
+    let entities = entities(&mut world, 4);
+    let mut pool = EntityPool::new(entities, &mut world);
+
+    let tree = pool.get_tree(&[1, 0, 0]);
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree.parent_of(1), Some(tree.root()));
+}
+
+#[test]
+#[should_panic(expected = "tree describes children of entity 2 but only has 2 entities")]
+fn trees_reject_children_of_missing_entities() {
+    let mut world = World::new();
+    let entities = entities(&mut world, 4);
+    let mut pool = EntityPool::new(entities, &mut world);
+
+    pool.get_tree(&[1, 0, 1]);
+}
+
+#[test]
+fn blocks_span_allocator_groups() {
+    let mut world = World::new();
+    let entities = entities(&mut world, 8);
+    let mut pool = EntityPool::with_allocator(entities, &mut world, GroupedAllocator::new(3));
+
+    let _first = pool.get();
+    let block = pool.get_block(5);
+    assert_eq!(block.handles()[0].key().index(), 1);
+    assert_eq!(pool.stats().in_use, 6);
+
+    let rest: Vec<_> = std::iter::from_fn(|| pool.try_get()).collect();
+    assert_eq!(rest.len(), 2);
+}
+
+/// Loses track of slots taken for blocks.
+#[derive(Default)]
+struct Forgetful(StackAllocator);
+
+impl SlotAllocator for Forgetful {
+    fn grow(&mut self, slots: Range<u32>) {
+        self.0.grow(slots);
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        self.0.allocate()
+    }
+
+    fn take(&mut self, slot: u32) -> bool {
+        self.0.take(slot)
+    }
+
+    fn take_range(&mut self, _: Range<u32>) -> bool {
+        false
+    }
+
+    fn free(&mut self, slot: u32) {
+        self.0.free(slot);
+    }
+
+    fn available(&self) -> usize {
+        self.0.available()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+#[test]
+#[should_panic(expected = "allocator didn't report free slots 0..2 as free")]
+fn blocks_check_the_allocator() {
+    let mut world = World::new();
+    let entities = entities(&mut world, 2);
+    let mut pool = EntityPool::with_allocator(entities, &mut world, Forgetful::default());
+
+    pool.get_block(2);
+}
@@ -0,0 +1,88 @@
+use bevy::{prelude::*, render::view::Visibility};
+use bevy_entity_pool::{
+    quarantine, EntityPool, QuarantinePlugin, QuarantineSet, ScratchIsolation,
+    ScratchIsolationPlugin, SlotsCommitted,
+};
+
+#[derive(Component, Debug, PartialEq)]
+struct Collider;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        ScratchIsolationPlugin,
+        QuarantinePlugin {
+            set: QuarantineSet::default().with::<Collider>(),
+        },
+    ));
+    let entities = app.world.entities().reserve_entities(3).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app
+}
+
+#[test]
+fn committed_entities_go_live_together() {
+    let mut app = app();
+    let handles: Vec<_> = app
+        .world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| {
+            (0..2).map(|_| pool.get_isolated(world)).collect()
+        });
+    for handle in &handles {
+        app.world
+            .entity_mut(handle.entity())
+            .insert((Visibility::Visible, Collider));
+        quarantine(&mut app.world, handle.entity());
+    }
+    app.update();
+    assert!(handles.iter().all(|handle| {
+        let entity = app.world.entity(handle.entity());
+        entity.get::<Visibility>() == Some(&Visibility::Hidden)
+            && entity.get::<Collider>().is_none()
+    }));
+
+    app.world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| pool.commit(world, &handles));
+
+    let entities: Vec<Entity> = handles.iter().map(|handle| handle.entity()).collect();
+    for &entity in &entities {
+        let entity = app.world.entity(entity);
+        assert_eq!(entity.get::<Visibility>(), Some(&Visibility::Visible));
+        assert_eq!(entity.get::<Collider>(), Some(&Collider));
+        assert!(entity.get::<ScratchIsolation>().is_none());
+    }
+    let pool = app.world.resource::<EntityPool>();
+    assert!(handles.iter().all(|handle| pool.is_valid(handle.key())));
+    let events = app.world.resource::<Events<SlotsCommitted>>();
+    let committed: Vec<_> = events.get_reader().read(events).cloned().collect();
+    assert_eq!(committed.len(), 1);
+    assert_eq!(committed[0].entities, entities);
+}
+
+#[test]
+fn stale_handles_fail_the_whole_commit() {
+    let mut app = app();
+    let (live, stale) = app
+        .world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| {
+            let live = pool.get_isolated(world);
+            let stale = pool.get_isolated(world);
+            pool.release(stale.key(), world);
+            (live, stale)
+        });
+    app.world
+        .entity_mut(live.entity())
+        .insert(Visibility::Visible);
+    app.update();
+
+    let handles = [live, stale];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        app.world
+            .resource_scope(|world, mut pool: Mut<EntityPool>| pool.commit(world, &handles));
+    }));
+    assert!(result.is_err());
+    let live = app.world.entity(handles[0].entity());
+    assert_eq!(live.get::<Visibility>(), Some(&Visibility::Hidden));
+    assert!(live.get::<ScratchIsolation>().is_some());
+}
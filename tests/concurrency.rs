@@ -0,0 +1,68 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, EntityPool, PooledTaskFinished, PooledTaskPlugin, PooledTasks,
+    TaskConcurrency,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[test]
+fn held_back_tasks_check_out_entities_on_start_in_spawn_order() {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ));
+    let entities = app.world.entities().reserve_entities(4).collect();
+    let mut pool = EntityPool::new(entities, &mut app.world);
+    pool.set_task_concurrency(Some(TaskConcurrency::new(1, 1)));
+    app.world.insert_resource(pool);
+    app.update();
+
+    let release = Arc::new(AtomicBool::new(false));
+    let ids: Vec<_> = [2, 4, 1]
+        .into_iter()
+        .map(|count| {
+            let release = release.clone();
+            spawn_pooled_task(&mut app.world, count, move |_| {
+                while !release.load(Ordering::Acquire) {
+                    std::thread::yield_now();
+                }
+            })
+        })
+        .collect();
+
+    // only the first task holds entities, the second couldn't get 4 of them yet
+    assert_eq!(app.world.resource::<EntityPool>().stats().in_use, 2);
+    assert_eq!(app.world.resource::<PooledTasks>().running(), 1);
+
+    release.store(true, Ordering::Release);
+    let mut finished = Vec::new();
+    for _ in 0..1000 {
+        app.update();
+        let events = app.world.resource::<Events<PooledTaskFinished>>();
+        let new: Vec<_> = events
+            .get_reader()
+            .read(events)
+            .map(|event| event.id)
+            .filter(|id| !finished.contains(id))
+            .collect();
+        for id in new {
+            finished.push(id);
+            // handles are kept until taken, returning them lets later tasks start
+            app.world.resource_mut::<PooledTasks>().take_handles(id);
+        }
+        if finished.len() == ids.len() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    assert_eq!(finished, ids);
+}
@@ -0,0 +1,140 @@
+use bevy::{prelude::*, scene::DynamicScene};
+use bevy_entity_pool::{EntityPool, MirrorId, PoolCoordinator, SlotTemplate};
+
+#[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Component)]
+struct Marker(u32);
+
+fn main_world(capacity: u32) -> World {
+    let mut world = World::new();
+    world.init_resource::<AppTypeRegistry>();
+    world
+        .resource::<AppTypeRegistry>()
+        .write()
+        .register::<Marker>();
+    let entities = world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut world);
+    world.insert_resource(pool);
+    world
+}
+
+fn add_mirror(coordinator: &mut PoolCoordinator, world: &mut World) -> MirrorId {
+    world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| coordinator.add_mirror(&mut pool, world))
+}
+
+#[test]
+fn reclaimed_slots_are_cleared_in_mirrors() {
+    let mut world = main_world(2);
+    let mut coordinator = PoolCoordinator::default();
+    let mirror = add_mirror(&mut coordinator, &mut world);
+
+    let handle = world.resource_mut::<EntityPool>().get();
+    let entity = handle.entity();
+    coordinator
+        .mirror_mut(mirror)
+        .entity_mut(entity)
+        .insert(Marker(1));
+    drop(handle);
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| pool.reclaim(world));
+
+    coordinator.sync(&mut world);
+    assert_eq!(coordinator.mirror(mirror).get::<Marker>(entity), None);
+    assert!(coordinator.audit(&world).is_consistent());
+}
+
+#[test]
+fn mirrors_get_the_pool_template() {
+    let mut world = main_world(2);
+    let template = {
+        let mut scene_world = World::new();
+        scene_world.insert_resource(world.resource::<AppTypeRegistry>().clone());
+        scene_world.spawn(Marker(7));
+        SlotTemplate::from_scene(&DynamicScene::from_world(&scene_world))
+    };
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| pool.set_template(template, world));
+
+    let mut coordinator = PoolCoordinator::default();
+    let mirror = add_mirror(&mut coordinator, &mut world);
+    let entity = world.resource::<EntityPool>().entity(0);
+    assert_eq!(
+        coordinator.mirror(mirror).get::<Marker>(entity),
+        Some(&Marker(7))
+    );
+
+    coordinator
+        .mirror_mut(mirror)
+        .entity_mut(entity)
+        .insert(Marker(1));
+    coordinator.free_slot(&mut world, 0);
+    assert_eq!(
+        coordinator.mirror(mirror).get::<Marker>(entity),
+        Some(&Marker(7))
+    );
+}
+
+#[test]
+fn grown_entities_are_mirrored_on_sync() {
+    let mut world = main_world(1);
+    let mut coordinator = PoolCoordinator::default();
+    let mirror = add_mirror(&mut coordinator, &mut world);
+
+    world.resource::<EntityPool>().reserve(world.entities(), 2);
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| pool.flush(world));
+    coordinator.sync(&mut world);
+
+    let pool = world.resource::<EntityPool>();
+    assert_eq!(pool.capacity(), 3);
+    assert!(pool
+        .entities()
+        .iter()
+        .all(|&entity| coordinator.mirror(mirror).get_entity(entity).is_some()));
+}
+
+#[test]
+fn failed_grow_changes_no_world() {
+    let mut world = main_world(1);
+    let mut coordinator = PoolCoordinator::default();
+    let mirror = add_mirror(&mut coordinator, &mut world);
+
+    let grown: Vec<Entity> = world.entities().reserve_entities(1).collect();
+    // a newer generation of the grown index lives in the mirror
+    let stale = coordinator.mirror_mut(mirror).spawn_empty().id();
+    coordinator.mirror_mut(mirror).despawn(stale);
+    let occupant = coordinator.mirror_mut(mirror).spawn_empty().id();
+    assert_eq!(occupant.index(), grown[0].index());
+    assert_ne!(occupant, grown[0]);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        coordinator.grow(&mut world, grown.clone())
+    }));
+    assert!(result.is_err());
+    assert_eq!(world.resource::<EntityPool>().capacity(), 1);
+    assert!(world.get_entity(grown[0]).is_none());
+}
+
+#[test]
+fn audit_reports_diverged_free_slots() {
+    let mut world = main_world(2);
+    let mut coordinator = PoolCoordinator::default();
+    let mirror = add_mirror(&mut coordinator, &mut world);
+    let entity = world.resource::<EntityPool>().entity(1);
+    coordinator
+        .mirror_mut(mirror)
+        .entity_mut(entity)
+        .insert(Marker(1));
+
+    let audit = coordinator.audit(&world);
+    assert_eq!(audit.diverged_in_mirrors, [(mirror, vec![entity])]);
+    assert!(!audit.is_consistent());
+}
+
+#[test]
+#[should_panic(expected = "doesn't belong to the main world")]
+fn mirroring_another_worlds_pool_panics() {
+    let mut server = main_world(1);
+    let client = main_world(1);
+    let mut coordinator = PoolCoordinator::default();
+    server
+        .resource_scope(|_, mut pool: Mut<EntityPool>| coordinator.add_mirror(&mut pool, &client));
+}
@@ -0,0 +1,107 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, CopyBackDedup, EntityPool, PooledTaskFinished, PooledTaskPlugin, PooledTasks,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Component)]
+struct Tile {
+    kind: u32,
+}
+
+/// Reflected as an opaque value without `PartialEq` - can't be compared through reflection.
+#[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect_value(Component)]
+struct Opaque(u32);
+
+fn app(capacity: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ))
+    .register_type::<Tile>()
+    .register_type::<Opaque>();
+    let entities = app.world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app
+}
+
+/// Returns the entities of the first finished task.
+fn run_until_finished(app: &mut App) -> Vec<Entity> {
+    for _ in 0..1000 {
+        app.update();
+        let events = app.world.resource::<Events<PooledTaskFinished>>();
+        if let Some(finished) = events.get_reader().read(events).next() {
+            return finished.entities.clone();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    panic!("pooled task didn't finish");
+}
+
+fn enable_dedup(app: &mut App) -> Arc<Mutex<Vec<(Entity, Entity)>>> {
+    let duplicates = Arc::new(Mutex::new(Vec::new()));
+    let recorded = duplicates.clone();
+    app.world
+        .resource_mut::<PooledTasks>()
+        .set_copy_back_dedup(Some(CopyBackDedup::new(move |_, duplicate| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((duplicate.entity, duplicate.original));
+        })));
+    duplicates
+}
+
+#[test]
+fn identical_slots_are_deduplicated() {
+    let mut app = app(4);
+    let duplicates = enable_dedup(&mut app);
+    spawn_pooled_task(&mut app.world, 4, |context| {
+        for (index, &entity) in context.entities().to_vec().iter().enumerate() {
+            let kind = if index == 3 { 7 } else { 1 };
+            context.world.entity_mut(entity).insert(Tile { kind });
+        }
+    });
+
+    let entities = run_until_finished(&mut app);
+    let tiles: Vec<_> = entities
+        .iter()
+        .map(|&entity| app.world.get::<Tile>(entity).map(|tile| tile.kind))
+        .collect();
+    assert_eq!(tiles, [Some(1), None, None, Some(7)]);
+    assert_eq!(
+        *duplicates.lock().unwrap(),
+        [(entities[1], entities[0]), (entities[2], entities[0])]
+    );
+}
+
+#[test]
+fn incomparable_values_are_never_deduplicated() {
+    let mut app = app(2);
+    let duplicates = enable_dedup(&mut app);
+    spawn_pooled_task(&mut app.world, 2, |context| {
+        for (index, &entity) in context.entities().to_vec().iter().enumerate() {
+            context
+                .world
+                .entity_mut(entity)
+                .insert(Opaque(index as u32 + 1));
+        }
+    });
+
+    let entities = run_until_finished(&mut app);
+    let values: Vec<_> = entities
+        .iter()
+        .map(|&entity| app.world.get::<Opaque>(entity).map(|value| value.0))
+        .collect();
+    assert_eq!(values, [Some(1), Some(2)]);
+    assert!(duplicates.lock().unwrap().is_empty());
+}
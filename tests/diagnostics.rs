@@ -0,0 +1,80 @@
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsPlugin, DiagnosticsStore},
+    prelude::*,
+};
+use bevy_entity_pool::{EntityPool, EntityPoolDiagnosticsPlugin};
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn counters_track_peak_and_failures() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 2);
+    let first = pool.get();
+    let second = pool.get();
+    assert!(pool.try_get().is_none());
+    drop(second);
+    pool.reclaim(&mut world);
+    let _third = pool.get();
+
+    let stats = pool.stats();
+    assert_eq!(stats.capacity, 2);
+    assert_eq!(stats.in_use, 2);
+    assert_eq!(stats.peak_in_use, 2);
+    assert_eq!(stats.total_acquisitions, 3);
+    assert_eq!(stats.failed_acquisitions, 1);
+    drop(first);
+}
+
+#[test]
+fn leaks_report_handles_still_held() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 3);
+    pool.set_leak_detection(true);
+    let leaked = pool.get();
+    let dropped = pool.get();
+    drop(dropped);
+
+    pool.free_entities(&mut world);
+
+    let leaks = pool.take_leaks();
+    assert_eq!(leaks.len(), 1);
+    assert_eq!(
+        (leaks[0].key, leaks[0].entity),
+        (leaked.key(), leaked.entity())
+    );
+    assert_eq!(leaks[0].acquired_at.file(), file!());
+    assert!(pool.leaks().is_empty());
+}
+
+#[test]
+fn leaks_are_not_tracked_while_disabled() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 1);
+    let _leaked = pool.get();
+    pool.free_entities(&mut world);
+    assert!(pool.leaks().is_empty());
+}
+
+#[test]
+fn plugin_reports_pool_usage() {
+    let mut app = App::new();
+    app.add_plugins((DiagnosticsPlugin, EntityPoolDiagnosticsPlugin::default()));
+    let mut pool = pool(&mut app.world, 4);
+    let _handles: Vec<_> = (0..3).map(|_| pool.get()).collect();
+    app.world.insert_resource(pool);
+
+    app.update();
+    let diagnostics = app.world.resource::<DiagnosticsStore>();
+    let value = |path: DiagnosticPath| diagnostics.get(&path).and_then(|d| d.value());
+    assert_eq!(value(EntityPoolDiagnosticsPlugin::IN_USE), Some(3.0));
+    assert_eq!(value(EntityPoolDiagnosticsPlugin::PEAK_IN_USE), Some(3.0));
+    assert_eq!(value(EntityPoolDiagnosticsPlugin::ACQUISITIONS), Some(3.0));
+    assert_eq!(
+        value(EntityPoolDiagnosticsPlugin::FAILED_ACQUISITIONS),
+        Some(0.0)
+    );
+}
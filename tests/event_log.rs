@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{EntityPool, PoolOp};
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn log_keeps_the_last_operations() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 1);
+    pool.enable_event_log(3);
+
+    let handle = pool.get();
+    pool.set_log_label(Some("job"));
+    assert!(pool.try_get().is_none());
+    pool.release(handle.key(), &mut world);
+    pool.next_generation();
+
+    let ops: Vec<_> = pool.event_log().map(|entry| entry.op).collect();
+    assert_eq!(
+        ops,
+        [
+            PoolOp::Fail,
+            PoolOp::Free { slot: 0 },
+            PoolOp::NextGeneration { generation: 1 }
+        ]
+    );
+    let entries: Vec<_> = pool.event_log().collect();
+    assert_eq!(entries[0].in_use, 1);
+    assert_eq!(entries[1].in_use, 0);
+    assert!(entries
+        .iter()
+        .all(|entry| entry.label.as_deref() == Some("job")));
+}
+
+#[test]
+fn disabled_logs_record_nothing() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 1);
+    let _handle = pool.get();
+    assert_eq!(pool.event_log().count(), 0);
+    assert!(pool.dump_event_log().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "recent operations:")]
+fn exhaustion_panics_include_the_log() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 1);
+    pool.enable_event_log(8);
+    let _handle = pool.get();
+    pool.get();
+}
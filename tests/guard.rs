@@ -0,0 +1,75 @@
+use bevy::{ecs::event::ManualEventReader, prelude::*};
+use bevy_entity_pool::{EntityPool, PoolEntitiesLost, PoolGuardPlugin};
+
+fn app(capacity: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins(PoolGuardPlugin);
+    // pooled entities get generation 1, entities spawned after a clear start over at 0
+    let first = app.world.spawn_empty().id();
+    app.world.despawn(first);
+    let entities = app.world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app
+}
+
+fn lost(app: &App, reader: &mut ManualEventReader<PoolEntitiesLost>) -> Vec<PoolEntitiesLost> {
+    reader
+        .read(app.world.resource::<Events<PoolEntitiesLost>>())
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn cleared_entities_are_recovered() {
+    let mut app = app(2);
+    let handle = app.world.resource_mut::<EntityPool>().get();
+    let mut reader = ManualEventReader::default();
+
+    app.world.clear_entities();
+    app.update();
+
+    let events = lost(&app, &mut reader);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].recovered.len(), 2);
+    assert!(events[0].conflicts.is_empty());
+    assert_eq!(events[0].invalidated, 1);
+
+    let pool = app.world.resource::<EntityPool>();
+    assert!(!pool.is_valid(handle.key()));
+    assert_eq!(pool.stats().in_use, 0);
+    assert!(app.world.get_entity(handle.entity()).is_some());
+}
+
+#[test]
+fn conflicts_are_reported_once() {
+    let mut app = app(1);
+    let pooled = app.world.resource::<EntityPool>().entities()[0];
+    let mut reader = ManualEventReader::default();
+
+    app.world.clear_entities();
+    let intruder = app.world.spawn_empty().id();
+    assert_eq!(intruder.index(), pooled.index());
+    assert_ne!(intruder, pooled);
+    let mut events = Vec::new();
+    for _ in 0..3 {
+        app.update();
+        events.extend(lost(&app, &mut reader));
+    }
+
+    assert_eq!(events.len(), 1);
+    assert!(events[0].recovered.is_empty());
+    assert_eq!(events[0].conflicts, [pooled]);
+    assert_eq!(events[0].invalidated, 0);
+    let mut pool = app.world.resource_mut::<EntityPool>();
+    assert_eq!(pool.stats().in_use, 0);
+    assert!(pool.try_get().is_none());
+
+    app.world.despawn(intruder);
+    app.update();
+    let events = lost(&app, &mut reader);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].recovered, [pooled]);
+    assert_eq!(events[0].invalidated, 0);
+    assert!(app.world.resource_mut::<EntityPool>().try_get().is_some());
+}
@@ -0,0 +1,60 @@
+use bevy::{
+    prelude::*,
+    render::view::{RenderLayers, Visibility},
+};
+use bevy_entity_pool::{strip_isolation, EntityPool, ScratchIsolationPlugin};
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(ScratchIsolationPlugin);
+    let entities = app.world.entities().reserve_entities(2).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app
+}
+
+#[test]
+fn visibility_assigned_on_the_checkout_frame_is_hidden() {
+    let mut app = app();
+    let handle = app
+        .world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| pool.get_isolated(world));
+    app.world
+        .entity_mut(handle.entity())
+        .insert((Visibility::Visible, RenderLayers::layer(1)));
+
+    app.update();
+    let entity = app.world.entity(handle.entity());
+    assert_eq!(entity.get::<Visibility>(), Some(&Visibility::Hidden));
+    assert_eq!(entity.get::<RenderLayers>(), Some(&RenderLayers::none()));
+
+    strip_isolation(&mut app.world, handle.entity());
+    let entity = app.world.entity(handle.entity());
+    assert_eq!(entity.get::<Visibility>(), Some(&Visibility::Visible));
+    assert_eq!(entity.get::<RenderLayers>(), Some(&RenderLayers::layer(1)));
+}
+
+#[test]
+fn isolated_entities_stay_hidden_across_frames() {
+    let mut app = app();
+    let handle = app
+        .world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| pool.get_isolated(world));
+    app.update();
+
+    app.world
+        .entity_mut(handle.entity())
+        .insert(Visibility::Inherited);
+    app.update();
+    assert_eq!(
+        app.world.get::<Visibility>(handle.entity()),
+        Some(&Visibility::Hidden)
+    );
+
+    strip_isolation(&mut app.world, handle.entity());
+    assert_eq!(
+        app.world.get::<Visibility>(handle.entity()),
+        Some(&Visibility::Inherited)
+    );
+    assert_eq!(app.world.get::<RenderLayers>(handle.entity()), None);
+}
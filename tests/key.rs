@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{EntityPool, RawSlot, SlotKey};
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn raw_slots_round_trip() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 2);
+    let key = pool.get().key();
+
+    let raw = key.to_raw();
+    assert_eq!((raw.index, raw.generation), (key.index(), key.generation()));
+    assert_eq!(SlotKey::from_raw(raw), key);
+    assert_eq!(SlotKey::from(RawSlot::from(key)), key);
+    assert_eq!(SlotKey::from_bits(key.to_bits()), key);
+    assert_eq!(
+        key.to_bits(),
+        u64::from(key.generation()) << 32 | u64::from(key.index())
+    );
+    assert_eq!(std::mem::size_of::<RawSlot>(), 8);
+    assert_eq!(std::mem::align_of::<RawSlot>(), 4);
+}
+
+#[test]
+fn raw_keys_are_validated_against_the_pool() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 2);
+    let handle = pool.get();
+    let raw = handle.key().to_raw();
+    assert_eq!(pool.resolve(SlotKey::from_raw(raw)), Some(handle.entity()));
+
+    assert!(pool.release(SlotKey::from_raw(raw), &mut world));
+    assert_eq!(pool.resolve(SlotKey::from_raw(raw)), None);
+    assert!(!pool.release(SlotKey::from_raw(raw), &mut world));
+
+    let reused = pool.get();
+    assert_eq!(reused.key().index(), raw.index);
+    assert_ne!(reused.key().generation(), raw.generation);
+
+    let out_of_range = RawSlot {
+        index: 7,
+        generation: 0,
+    };
+    assert_eq!(pool.resolve(out_of_range.into()), None);
+}
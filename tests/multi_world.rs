@@ -0,0 +1,112 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    diagnostic::{DiagnosticsPlugin, DiagnosticsStore},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    scripting::{self, ScriptPoolError, ScriptPools},
+    EntityPool, EntityPoolDiagnosticsPlugin, PoolDiagnosticPaths, PooledTaskPlugin,
+};
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+fn app(label: &str, capacity: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        DiagnosticsPlugin,
+        PooledTaskPlugin,
+        EntityPoolDiagnosticsPlugin::labeled(label),
+    ));
+    let pool = pool(&mut app.world, capacity);
+    app.world.insert_resource(pool);
+    app
+}
+
+#[test]
+fn pools_know_their_world() {
+    let mut server = World::new();
+    let mut client = World::new();
+    let mut server_pool = pool(&mut server, 2);
+    let mut client_pool = pool(&mut client, 2);
+
+    assert_eq!(server_pool.world_id(), server.id());
+    assert!(server_pool.belongs_to(&server));
+    assert!(!server_pool.belongs_to(&client));
+    assert!(client_pool.belongs_to(&client));
+
+    // both worlds hand out the same ids, only the handles tell them apart
+    let server_handle = server_pool.get();
+    let client_handle = client_pool.get();
+    assert_eq!(server_handle.entity(), client_handle.entity());
+    assert_eq!(server_handle.key(), client_handle.key());
+    assert!(server_pool.owns(&server_handle));
+    assert!(!server_pool.owns(&client_handle));
+    assert!(client_pool.owns(&client_handle));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "belongs to")]
+fn using_a_pool_with_another_world_panics() {
+    let mut server = World::new();
+    let mut client = World::new();
+    let mut server_pool = pool(&mut server, 1);
+    server_pool.reclaim(&mut client);
+}
+
+#[test]
+fn labeled_diagnostics_keep_worlds_apart() {
+    let mut server = app("server", 4);
+    let mut client = app("client", 4);
+    let _server_handles: Vec<_> = (0..3)
+        .map(|_| server.world.resource_mut::<EntityPool>().get())
+        .collect();
+    let _client_handle = client.world.resource_mut::<EntityPool>().get();
+    server.update();
+    client.update();
+
+    let in_use = |app: &App, label: &str| {
+        let path = PoolDiagnosticPaths::labeled(label).in_use;
+        app.world
+            .resource::<DiagnosticsStore>()
+            .get(&path)
+            .and_then(|diagnostic| diagnostic.value())
+    };
+    assert_eq!(
+        PoolDiagnosticPaths::labeled("server").in_use.as_str(),
+        "server/entity_pool/in_use"
+    );
+    assert_eq!(in_use(&server, "server"), Some(3.0));
+    assert_eq!(in_use(&client, "client"), Some(1.0));
+    assert_eq!(in_use(&server, "client"), None);
+}
+
+#[test]
+fn script_pools_reject_pools_of_other_worlds() {
+    let mut server = World::new();
+    let mut client = World::new();
+    let mut pools = ScriptPools::default();
+    pools.register("props", pool(&mut client, 1));
+    server.insert_resource(pools);
+
+    assert_eq!(
+        scripting::acquire_by_name(&mut server, "props"),
+        Err(ScriptPoolError::ForeignPool("props".to_string()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "but registered pools belong to")]
+fn script_pools_of_mixed_worlds_panic() {
+    let mut server = World::new();
+    let mut client = World::new();
+    let mut pools = ScriptPools::default();
+    pools.register("server", pool(&mut server, 1));
+    pools.register("client", pool(&mut client, 1));
+}
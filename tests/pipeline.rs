@@ -0,0 +1,154 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, EntityPool, PooledTaskFinished, PooledTaskId, PooledTaskPlugin, PooledTasks,
+    ScratchContext,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Value(u32);
+
+fn app(capacity: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ))
+    .register_type::<Value>();
+    let entities = app.world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app.update();
+    app
+}
+
+/// Updates until `done` or a frame budget runs out, collecting finished tasks in merge order.
+fn run_until(app: &mut App, finished: &mut Vec<PooledTaskId>, done: impl Fn(&App) -> bool) {
+    for _ in 0..1000 {
+        if done(app) {
+            return;
+        }
+        app.update();
+        let events = app.world.resource::<Events<PooledTaskFinished>>();
+        for event in events.get_reader().read(events) {
+            if !finished.contains(&event.id) {
+                finished.push(event.id);
+            }
+        }
+        std::thread::yield_now();
+    }
+    panic!("tasks didn't finish");
+}
+
+fn fill(context: &mut ScratchContext, value: u32) {
+    for entity in context.entities().to_vec() {
+        context.world.entity_mut(entity).insert(Value(value));
+    }
+}
+
+#[test]
+fn fenced_jobs_merge_after_their_dependency() {
+    let mut app = app(4);
+    let release = Arc::new(AtomicBool::new(false));
+
+    let first = {
+        let release = release.clone();
+        spawn_pooled_task(&mut app.world, 2, move |context| {
+            while !release.load(Ordering::Acquire) {
+                std::thread::yield_now();
+            }
+            fill(context, 1);
+        })
+    };
+    let copied_back = app
+        .world
+        .resource::<PooledTasks>()
+        .fences(first)
+        .unwrap()
+        .copied_back
+        .clone();
+    let handles: Vec<_> = {
+        let mut pool = app.world.resource_mut::<EntityPool>();
+        (0..2).map(|_| pool.get()).collect()
+    };
+    let entities: Vec<Entity> = handles.iter().map(|handle| handle.entity()).collect();
+    let second = app.world.resource_mut::<PooledTasks>().spawn_staged(
+        handles,
+        vec![copied_back],
+        |context| {
+            fill(context, 2);
+        },
+    );
+
+    for _ in 0..20 {
+        app.update();
+    }
+    let tasks = app.world.resource::<PooledTasks>();
+    assert!(tasks.is_running(first));
+    assert!(!tasks.fences(second).unwrap().copied_in.is_signaled());
+    assert!(entities
+        .iter()
+        .all(|&e| app.world.get::<Value>(e).is_none()));
+
+    release.store(true, Ordering::Release);
+    let mut finished = Vec::new();
+    run_until(&mut app, &mut finished, |app| {
+        !app.world.resource::<PooledTasks>().is_running(second)
+    });
+    assert_eq!(finished, [first, second]);
+    assert!(entities
+        .iter()
+        .all(|&e| app.world.get::<Value>(e) == Some(&Value(2))));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "changed read-only slot")]
+fn stages_mutating_published_slots_panic() {
+    let mut app = app(2);
+    let handle = app.world.resource_mut::<EntityPool>().get();
+    app.world.entity_mut(handle.entity()).insert(Value(1));
+    app.world.resource_mut::<EntityPool>().publish(&handle);
+
+    let stage =
+        app.world
+            .resource_mut::<PooledTasks>()
+            .spawn_staged(vec![handle], Vec::new(), |context| {
+                let entity = context.read_only()[0];
+                context.world.get_mut::<Value>(entity).unwrap().0 = 2;
+            });
+    run_until(&mut app, &mut Vec::new(), |app| {
+        !app.world.resource::<PooledTasks>().is_running(stage)
+    });
+}
+
+#[test]
+fn published_slots_are_inputs_only() {
+    let mut app = app(2);
+    let handle = app.world.resource_mut::<EntityPool>().get();
+    let entity = handle.entity();
+    app.world.entity_mut(entity).insert(Value(1));
+    app.world.resource_mut::<EntityPool>().publish(&handle);
+
+    let stage = app.world.resource_mut::<PooledTasks>().spawn_staged(
+        vec![handle],
+        Vec::new(),
+        move |context| {
+            assert_eq!(context.read_only(), [entity]);
+            assert_eq!(context.world.get::<Value>(entity), Some(&Value(1)));
+        },
+    );
+    run_until(&mut app, &mut Vec::new(), |app| {
+        !app.world.resource::<PooledTasks>().is_running(stage)
+    });
+    assert_eq!(app.world.get::<Value>(entity), Some(&Value(1)));
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "profiling")]
+
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_entity_pool::{
+    AcquisitionGrouping, AcquisitionScope, EntityPool, ProfiledPool, MAX_ACQUISITION_CONTEXTS,
+};
+use std::sync::Arc;
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn per_chunk_reasons_are_capped() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 1);
+    let chunks = MAX_ACQUISITION_CONTEXTS + 10;
+    for chunk in 0..chunks {
+        pool.set_acquisition_scope(AcquisitionScope {
+            reason: Some(format!("chunk {chunk}").into()),
+            ..default()
+        });
+        drop(pool.get());
+        pool.reclaim(&mut world);
+    }
+
+    let groups = pool.acquisitions_by(AcquisitionGrouping::Reason);
+    assert_eq!(groups.len(), MAX_ACQUISITION_CONTEXTS + 1);
+    let (_, other) = groups.iter().find(|(name, _)| name == "<other>").unwrap();
+    assert_eq!(other.total, 10);
+    let total: u64 = groups.iter().map(|(_, summary)| summary.total).sum();
+    assert_eq!(total, chunks as u64);
+}
+
+#[test]
+fn profiled_acquisitions_share_names() {
+    let mut world = World::new();
+    let pool = pool(&mut world, 2);
+    world.insert_resource(pool);
+
+    let handles =
+        world.run_system_once(|mut pool: ProfiledPool| [pool.get("chunk"), pool.get("chunk")]);
+    let pool = world.resource::<EntityPool>();
+    let [a, b] = handles.map(|handle| pool.acquisition_context(handle.key()).unwrap().clone());
+
+    assert_eq!(a.reason.as_deref(), Some("chunk"));
+    assert!(Arc::ptr_eq(
+        a.reason.as_ref().unwrap(),
+        b.reason.as_ref().unwrap()
+    ));
+    assert!(Arc::ptr_eq(
+        a.system.as_ref().unwrap(),
+        b.system.as_ref().unwrap()
+    ));
+    assert_eq!(pool.acquisition_scope(), &AcquisitionScope::default());
+}
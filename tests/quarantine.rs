@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{
+    quarantine, release_quarantine, EntityPool, Quarantine, QuarantinePlugin, QuarantineSet,
+    Quarantined,
+};
+
+#[derive(Component, Debug, PartialEq)]
+struct Collider(u32);
+
+#[derive(Component, Debug, PartialEq)]
+struct Mesh;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(QuarantinePlugin {
+        set: QuarantineSet::default().with::<Collider>(),
+    });
+    let entities = app.world.entities().reserve_entities(2).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app
+}
+
+#[test]
+fn quarantined_components_are_inert_until_released() {
+    let mut app = app();
+    let handle = app
+        .world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| pool.get_quarantined(world));
+    let entity = handle.entity();
+    app.world.entity_mut(entity).insert((Collider(3), Mesh));
+
+    app.update();
+    let quarantined = app.world.entity(entity);
+    assert!(quarantined.get::<Collider>().is_none());
+    assert_eq!(
+        quarantined.get::<Quarantined<Collider>>().map(|c| &c.0),
+        Some(&Collider(3))
+    );
+    assert_eq!(quarantined.get::<Mesh>(), Some(&Mesh));
+
+    release_quarantine(&mut app.world, entity);
+    app.update();
+    let released = app.world.entity(entity);
+    assert_eq!(released.get::<Collider>(), Some(&Collider(3)));
+    assert!(released.get::<Quarantined<Collider>>().is_none());
+    assert!(released.get::<Quarantine>().is_none());
+}
+
+#[test]
+fn existing_components_are_quarantined_immediately() {
+    let mut app = app();
+    let entity = app.world.spawn(Collider(1)).id();
+
+    quarantine(&mut app.world, entity);
+    assert!(app.world.get::<Collider>(entity).is_none());
+    assert!(app.world.get::<Quarantined<Collider>>(entity).is_some());
+}
+
+#[test]
+fn releasing_unquarantined_entities_does_nothing() {
+    let mut app = app();
+    let entity = app.world.spawn(Quarantined(Collider(1))).id();
+
+    release_quarantine(&mut app.world, entity);
+    assert!(app.world.get::<Collider>(entity).is_none());
+    assert!(app.world.get::<Quarantined<Collider>>(entity).is_some());
+}
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{EntityPool, Reclaim};
+
+#[derive(Component)]
+struct Marker;
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn marked_entities_return_to_the_pool() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 2);
+    let handle = pool.get();
+    let other = pool.get();
+    world.entity_mut(handle.entity()).insert((Marker, Reclaim));
+
+    pool.reclaim(&mut world);
+
+    assert!(!pool.is_valid(handle.key()));
+    assert!(pool.is_valid(other.key()));
+    assert_eq!(pool.stats().in_use, 1);
+    assert!(world.get::<Marker>(handle.entity()).is_none());
+    assert!(world.get::<Reclaim>(handle.entity()).is_none());
+    assert_eq!(pool.get().entity(), handle.entity());
+}
+
+#[test]
+fn marked_entities_outside_the_pool_are_left_alone() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 1);
+    let handle = pool.get();
+    let outsider = world.spawn((Marker, Reclaim)).id();
+
+    pool.reclaim(&mut world);
+
+    assert!(pool.is_valid(handle.key()));
+    assert!(world.get::<Marker>(outsider).is_some());
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{EntityPool, PoolRegistry, PooledSlotMap};
+
+#[derive(Component)]
+struct Value;
+
+#[test]
+fn dropping_a_pool_releases_its_ranges() {
+    let mut world = World::new();
+    world.init_resource::<PoolRegistry>();
+    let entities: Vec<Entity> = world.entities().reserve_entities(4).collect();
+
+    let pool = EntityPool::new(entities.clone(), &mut world);
+    let id = pool.id();
+    assert_eq!(
+        world.resource::<PoolRegistry>().owner(entities[0]),
+        Some(id)
+    );
+    drop(pool);
+
+    assert_eq!(world.resource::<PoolRegistry>().owner(entities[0]), None);
+    let pool = EntityPool::new(entities.clone(), &mut world);
+    assert_eq!(
+        world.resource::<PoolRegistry>().owner(entities[3]),
+        Some(pool.id())
+    );
+}
+
+#[test]
+fn replacing_a_pool_resource_releases_its_ranges() {
+    let mut world = World::new();
+    world.init_resource::<PoolRegistry>();
+    let entities: Vec<Entity> = world.entities().reserve_entities(2).collect();
+
+    let pool = EntityPool::new(entities.clone(), &mut world);
+    world.insert_resource(pool);
+    world.remove_resource::<EntityPool>();
+    let pool = EntityPool::new(entities.clone(), &mut world);
+    let id = pool.id();
+    world.insert_resource(pool);
+
+    let registry = world.resource::<PoolRegistry>();
+    assert!(entities
+        .iter()
+        .all(|&entity| registry.owner(entity) == Some(id)));
+}
+
+#[test]
+fn registering_owned_indices_again_merges_ranges() {
+    let mut world = World::new();
+    let entities: Vec<Entity> = world.entities().reserve_entities(4).collect();
+    let start = entities[0].index();
+    let pool = EntityPool::new(Vec::new(), &mut world).id();
+    let mut registry = PoolRegistry::default();
+
+    registry.register(pool, &entities[..3]).unwrap();
+    registry.register(pool, &entities[..3]).unwrap();
+    registry.register(pool, &entities[2..]).unwrap();
+
+    let mut ranges = registry.ranges(pool);
+    assert_eq!(ranges.next(), Some(start..start + 4));
+    assert_eq!(ranges.next(), None);
+}
+
+#[test]
+fn grown_ranges_are_released_too() {
+    let mut world = World::new();
+    world.init_resource::<PoolRegistry>();
+    let first: Vec<Entity> = world.entities().reserve_entities(2).collect();
+    let more: Vec<Entity> = world.entities().reserve_entities(2).collect();
+
+    let mut pool = EntityPool::new(first, &mut world);
+    pool.grow(more.clone(), &mut world);
+    drop(pool);
+
+    assert_eq!(world.resource::<PoolRegistry>().owner(more[1]), None);
+}
+
+#[test]
+#[should_panic(expected = "Failed to register pool entities")]
+fn live_pools_still_conflict() {
+    let mut world = World::new();
+    world.init_resource::<PoolRegistry>();
+    let entities: Vec<Entity> = world.entities().reserve_entities(2).collect();
+
+    let _pool = EntityPool::new(entities.clone(), &mut world);
+    EntityPool::new(entities, &mut world);
+}
+
+#[test]
+fn slot_maps_can_swap_pools() {
+    let mut map = PooledSlotMap::<Value>::with_capacity(2);
+    map.world_mut().init_resource::<PoolRegistry>();
+    let (mut world, pool) = map.into_parts();
+    let entities: Vec<Entity> = world.entities().reserve_entities(2).collect();
+    let first = EntityPool::new(entities.clone(), &mut world);
+    drop(first);
+
+    let second = EntityPool::new(entities, &mut world);
+    drop(pool);
+    let mut map = PooledSlotMap::<Value>::from_parts(world, second);
+    map.insert(Value);
+}
@@ -0,0 +1,119 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, EntityPool, PooledTaskPlugin, PooledTasks, ScratchContext, ScratchSteps,
+};
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Value(u64);
+
+fn app(capacity: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ))
+    .register_type::<Value>();
+    let entities = app.world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app.update();
+    app
+}
+
+fn update_values(context: &mut ScratchContext, update: impl Fn(u64, &mut ScratchContext) -> u64) {
+    for entity in context.entities().to_vec() {
+        let value = context
+            .world
+            .get::<Value>(entity)
+            .map_or(1, |value| value.0);
+        let value = update(value, context);
+        context.world.entity_mut(entity).insert(Value(value));
+    }
+}
+
+fn steps() -> ScratchSteps {
+    ScratchSteps::new()
+        .step("roll", |context| {
+            update_values(context, |value, context| {
+                value + u64::from(context.rng.range(0..1000))
+            })
+        })
+        .step("double", |context| {
+            update_values(context, |value, _| value * 2)
+        })
+}
+
+#[test]
+fn replays_reproduce_recorded_tasks() {
+    let mut app = app(2);
+    let (task, recording) = steps().record();
+    let id = spawn_pooled_task(&mut app.world, 2, task);
+    for _ in 0..1000 {
+        if !app.world.resource::<PooledTasks>().is_running(id) {
+            break;
+        }
+        app.update();
+        std::thread::yield_now();
+    }
+    let recording = recording.get().expect("task didn't start");
+    assert_eq!(recording.steps().collect::<Vec<_>>(), ["roll", "double"]);
+    assert_eq!(
+        Some(recording.seed()),
+        app.world.resource::<PooledTasks>().task_seed(id)
+    );
+
+    let type_registry = app.world.resource::<AppTypeRegistry>().clone();
+    let mut replay = recording.replay(&type_registry);
+    let entities = recording.entities().to_vec();
+    assert!(entities
+        .iter()
+        .all(|&entity| replay.world().get::<Value>(entity).is_none()));
+
+    assert_eq!(replay.step(), Some("roll"));
+    assert_eq!(replay.next_step(), Some("double"));
+    replay.run_to_end();
+    assert!(replay.is_finished());
+    assert_eq!(replay.step(), None);
+    for entity in entities {
+        assert_eq!(
+            replay.world().get::<Value>(entity),
+            app.world.get::<Value>(entity)
+        );
+    }
+}
+
+#[test]
+fn replays_start_from_the_copied_in_world() {
+    let mut app = app(1);
+    let handle = app.world.resource_mut::<EntityPool>().get();
+    let entity = handle.entity();
+    app.world.entity_mut(entity).insert(Value(10));
+    let (task, recording) = steps().record();
+
+    let id = app
+        .world
+        .resource_mut::<PooledTasks>()
+        .spawn_staged(vec![handle], Vec::new(), task);
+    for _ in 0..1000 {
+        if !app.world.resource::<PooledTasks>().is_running(id) {
+            break;
+        }
+        app.update();
+        std::thread::yield_now();
+    }
+
+    let type_registry = app.world.resource::<AppTypeRegistry>().clone();
+    let mut replay = recording.get().unwrap().replay(&type_registry);
+    assert_eq!(replay.world().get::<Value>(entity), Some(&Value(10)));
+    replay.run_to_end();
+    assert_eq!(
+        replay.world().get::<Value>(entity),
+        app.world.get::<Value>(entity)
+    );
+}
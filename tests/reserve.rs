@@ -0,0 +1,106 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    ecs::{entity::Entities, system::RunSystemOnce},
+    prelude::*,
+};
+use bevy_entity_pool::{EntityPool, PooledTaskPlugin};
+
+fn world_with_pool(capacity: u32) -> World {
+    let mut world = World::new();
+    let entities = world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut world);
+    world.insert_resource(pool);
+    world
+}
+
+#[test]
+fn reservations_join_the_pool_on_flush() {
+    let mut world = world_with_pool(1);
+    world.run_system_once(|pool: Res<EntityPool>, entities: &Entities| {
+        pool.reserve(entities, 2);
+    });
+
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| {
+        assert_eq!(pool.pending_reservations(), 2);
+        let first = pool.get();
+        assert!(pool.try_get().is_none());
+
+        pool.flush(world);
+        assert_eq!(pool.pending_reservations(), 0);
+        assert_eq!(pool.capacity(), 3);
+        let more: Vec<_> = pool.acquire_while_available().collect();
+        assert_eq!(more.len(), 2);
+        assert!(more
+            .iter()
+            .all(|handle| world.get_entity(handle.entity()).is_some()));
+        drop(first);
+    });
+}
+
+#[test]
+fn live_acquisitions_flush_first() {
+    let mut world = world_with_pool(0);
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| {
+        pool.reserve(world.entities(), 1);
+        let handle = pool.get_live(world);
+        assert!(world.get_entity(handle.entity()).is_some());
+        assert!(pool.try_get_live(world).is_none());
+    });
+}
+
+#[test]
+fn flushing_spawns_reserved_entities_of_the_world() {
+    let mut world = world_with_pool(1);
+    let reserved = world.entities().reserve_entity();
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| pool.flush(world));
+
+    assert!(world.get_entity(reserved).is_some());
+    assert_eq!(world.resource::<EntityPool>().capacity(), 1);
+}
+
+#[test]
+fn exhausted_paths_wait_for_reservations() {
+    let mut world = world_with_pool(1);
+    world.resource_scope(|world, mut pool: Mut<EntityPool>| {
+        let held = pool.get();
+        pool.reserve(world.entities(), 1);
+        assert!(pool.try_get_hinted(7).is_none());
+        assert_eq!(pool.acquire_while_available().count(), 0);
+
+        pool.flush(world);
+        assert!(pool.try_get_hinted(7).is_some());
+        drop(held);
+    });
+}
+
+#[test]
+fn systems_reserve_in_parallel_and_the_plugin_flushes() {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ));
+    let entities = app.world.entities().reserve_entities(1).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+
+    let reserve = |pool: Res<EntityPool>, entities: &Entities| pool.reserve(entities, 2);
+    app.add_systems(Update, (reserve, reserve).run_if(run_once()));
+    app.update();
+    assert_eq!(app.world.resource::<EntityPool>().pending_reservations(), 4);
+
+    app.update();
+    let pool = app.world.resource::<EntityPool>();
+    assert_eq!(pool.pending_reservations(), 0);
+    assert_eq!(pool.capacity(), 5);
+    let mut indices: Vec<u32> = pool.entities().iter().map(|e| e.index()).collect();
+    indices.sort_unstable();
+    indices.dedup();
+    assert_eq!(indices.len(), 5);
+    assert!(pool
+        .entities()
+        .iter()
+        .all(|&entity| app.world.get_entity(entity).is_some()));
+}
@@ -0,0 +1,71 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, EntityPool, PooledTaskFinished, PooledTaskPlugin, PooledTasks, SlotKey,
+};
+
+#[test]
+fn keys_from_before_a_reset_stay_invalid() {
+    let mut world = World::new();
+    let entities = world.entities().reserve_entities(2).collect();
+    let mut pool = EntityPool::new(entities, &mut world);
+    let old = pool.get();
+    let raw = old.key().to_raw();
+
+    pool.reset(&mut world);
+    let new = pool.get();
+    assert_eq!(new.entity(), old.entity());
+    assert!(!pool.is_valid(old.key()));
+    assert_eq!(pool.resolve(SlotKey::from_raw(raw)), None);
+    assert_eq!(pool.resolve(new.key()), Some(new.entity()));
+}
+
+#[test]
+fn reset_adds_pending_reservations() {
+    let mut world = World::new();
+    let entities = world.entities().reserve_entities(2).collect();
+    let mut pool = EntityPool::new(entities, &mut world);
+    pool.reserve(world.entities(), 3);
+
+    pool.reset(&mut world);
+    assert_eq!(pool.pending_reservations(), 0);
+    assert_eq!(pool.capacity(), 5);
+    assert!(pool
+        .entities()
+        .iter()
+        .all(|&entity| world.get_entity(entity).is_some()));
+}
+
+#[test]
+fn reset_cancels_in_flight_tasks() {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ));
+    let entities = app.world.entities().reserve_entities(2).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+
+    let id = spawn_pooled_task(&mut app.world, 2, |context| {
+        while !context.progress.is_cancelled() {
+            std::thread::yield_now();
+        }
+    });
+    app.world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| pool.reset(world));
+    assert!(!app.world.resource::<PooledTasks>().is_running(id));
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert!(app
+        .world
+        .resource::<Events<PooledTaskFinished>>()
+        .is_empty());
+    assert_eq!(app.world.resource::<EntityPool>().stats().in_use, 0);
+}
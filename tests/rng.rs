@@ -0,0 +1,104 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, EntityPool, PooledTaskFinished, PooledTaskId, PooledTaskPlugin, PooledTasks,
+    ScratchContext, ScratchRng,
+};
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Value(u64);
+
+fn app(capacity: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ))
+    .register_type::<Value>();
+    let entities = app.world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app.update();
+    app
+}
+
+fn roll(context: &mut ScratchContext) {
+    for entity in context.entities().to_vec() {
+        let value = context.rng.next_u64();
+        context.world.entity_mut(entity).insert(Value(value));
+    }
+}
+
+/// Updates until task `id` finished, returning its event and the values merged by it.
+fn finish(app: &mut App, id: PooledTaskId) -> (PooledTaskFinished, Vec<u64>) {
+    for _ in 0..1000 {
+        app.update();
+        let events = app.world.resource::<Events<PooledTaskFinished>>();
+        let finished = events
+            .get_reader()
+            .read(events)
+            .find(|event| event.id == id)
+            .cloned();
+        if let Some(finished) = finished {
+            let values = finished
+                .entities
+                .iter()
+                .map(|&entity| app.world.get::<Value>(entity).unwrap().0)
+                .collect();
+            return (finished, values);
+        }
+        std::thread::yield_now();
+    }
+    panic!("task didn't finish");
+}
+
+#[test]
+fn generators_are_reproducible() {
+    let mut a = ScratchRng::new(7);
+    let mut b = ScratchRng::new(7);
+    let draws: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+    assert_eq!(draws, (0..8).map(|_| b.next_u64()).collect::<Vec<_>>());
+    assert_ne!(ScratchRng::new(8).next_u64(), draws[0]);
+
+    let mut rng = ScratchRng::new(1);
+    for _ in 0..1000 {
+        assert!((10..20).contains(&rng.range(10..20)));
+        assert!((0.0..1.0).contains(&rng.next_f32()));
+    }
+}
+
+#[test]
+fn reported_seeds_reproduce_results() {
+    let mut app = app(4);
+    app.world.resource_mut::<PooledTasks>().set_seed(42);
+
+    let id = spawn_pooled_task(&mut app.world, 2, roll);
+    let seed = app.world.resource::<PooledTasks>().task_seed(id);
+    let (finished, values) = finish(&mut app, id);
+    assert_eq!(seed, Some(finished.seed));
+
+    let type_registry = app.world.resource::<AppTypeRegistry>().clone();
+    let replay = app
+        .world
+        .resource_scope(|world, mut tasks: Mut<PooledTasks>| {
+            let mut pool = world.resource_mut::<EntityPool>();
+            tasks.spawn_with_seed(&mut pool, &type_registry, 2, finished.seed, roll)
+        });
+    let (replayed, replayed_values) = finish(&mut app, replay);
+    assert_eq!(replayed.seed, finished.seed);
+    assert_eq!(replayed_values, values);
+}
+
+#[test]
+fn tasks_get_distinct_seeds() {
+    let mut app = app(4);
+    let first = spawn_pooled_task(&mut app.world, 1, roll);
+    let second = spawn_pooled_task(&mut app.world, 1, roll);
+    let tasks = app.world.resource::<PooledTasks>();
+    assert_ne!(tasks.task_seed(first), tasks.task_seed(second));
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{ScheduleRun, ScratchSchedule, StepControl};
+
+#[derive(Resource, Default)]
+struct Counts(Vec<u32>);
+
+fn count(mut runs: Local<u32>, mut counts: ResMut<Counts>) {
+    *runs += 1;
+    counts.0.push(*runs);
+}
+
+#[test]
+fn adding_systems_keeps_existing_state() {
+    let mut world = World::new();
+    world.init_resource::<Counts>();
+    let mut schedule = ScratchSchedule::new();
+    schedule.add_system(count);
+    schedule.run(&mut world);
+
+    schedule.add_system(|| {});
+    schedule.run(&mut world);
+    assert_eq!(world.resource::<Counts>().0, [1, 2]);
+}
+
+#[test]
+fn adding_systems_while_paused_resumes() {
+    let mut world = World::new();
+    world.init_resource::<Counts>();
+    let mut schedule = ScratchSchedule::new();
+    schedule
+        .add_system(count)
+        .add_system(count)
+        .set_inspector(|step, _| {
+            if step.index == 0 {
+                StepControl::Pause
+            } else {
+                StepControl::Continue
+            }
+        });
+    assert_eq!(schedule.run(&mut world), ScheduleRun::Paused { next: 1 });
+
+    schedule.add_system(count);
+    assert_eq!(schedule.run(&mut world), ScheduleRun::Finished);
+    assert_eq!(world.resource::<Counts>().0, [1, 1, 1]);
+}
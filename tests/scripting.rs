@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use bevy_entity_pool::{
+    scripting::{self, ScriptPoolError, ScriptPools},
+    EntityPool,
+};
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Health(u32);
+
+fn world_with_pool() -> World {
+    let mut world = World::new();
+    let entities = world.entities().reserve_entities(1).collect();
+    let pool = EntityPool::new(entities, &mut world);
+    let mut pools = ScriptPools::default();
+    pools.register("props", pool);
+    world.insert_resource(pools);
+    world
+}
+
+#[test]
+fn inserting_without_type_registry_is_an_error() {
+    let mut world = world_with_pool();
+    let slot = scripting::acquire_by_name(&mut world, "props").unwrap();
+
+    assert_eq!(
+        scripting::insert_reflect(&mut world, slot, Box::new(Health(1))),
+        Err(ScriptPoolError::MissingTypeRegistry)
+    );
+}
+
+#[test]
+fn inserting_on_despawned_entity_is_an_error() {
+    let mut world = world_with_pool();
+    world.init_resource::<AppTypeRegistry>();
+    world
+        .resource::<AppTypeRegistry>()
+        .write()
+        .register::<Health>();
+    let slot = scripting::acquire_by_name(&mut world, "props").unwrap();
+    let entity = world.resource::<ScriptPools>().entity(slot).unwrap();
+    world.despawn(entity);
+
+    assert_eq!(
+        scripting::insert_reflect(&mut world, slot, Box::new(Health(1))),
+        Err(ScriptPoolError::DespawnedEntity(entity))
+    );
+}
+
+#[test]
+fn acquiring_a_despawned_entity_is_an_error() {
+    let mut world = world_with_pool();
+    let entity = world
+        .resource::<ScriptPools>()
+        .pool("props")
+        .unwrap()
+        .entity(0);
+    world.despawn(entity);
+
+    assert_eq!(
+        scripting::acquire_by_name(&mut world, "props"),
+        Err(ScriptPoolError::DespawnedEntity(entity))
+    );
+    assert_eq!(
+        world
+            .resource::<ScriptPools>()
+            .pool("props")
+            .unwrap()
+            .stats()
+            .in_use,
+        0
+    );
+}
+
+#[test]
+fn inserting_applies_the_component() {
+    let mut world = world_with_pool();
+    world.init_resource::<AppTypeRegistry>();
+    world
+        .resource::<AppTypeRegistry>()
+        .write()
+        .register::<Health>();
+    let slot = scripting::acquire_by_name(&mut world, "props").unwrap();
+
+    scripting::insert_reflect(&mut world, slot, Box::new(Health(3))).unwrap();
+    let entity = world.resource::<ScriptPools>().entity(slot).unwrap();
+    assert_eq!(world.get::<Health>(entity), Some(&Health(3)));
+}
@@ -0,0 +1,105 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_entity_pool::{EntityPool, SharedEntityHandle, SharedEntityPool};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn shared_pools_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SharedEntityPool>();
+    assert_send_sync::<SharedEntityHandle>();
+}
+
+#[test]
+fn empty_shards_steal_and_release_to_their_owner() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 4);
+    let shared = pool.share(4, 2);
+    let (first, second) = (shared.with_shard(0), shared.with_shard(1));
+    let owned_by_first: Vec<Entity> = pool_entities(&first, 2);
+
+    let own = [second.get(), second.get()];
+    let stolen = second.get();
+    assert!(owned_by_first.contains(&stolen.entity()));
+    assert!(own
+        .iter()
+        .all(|handle| !owned_by_first.contains(&handle.entity())));
+
+    // released back to the first shard, which hands it out before stealing
+    let entity = stolen.entity();
+    drop(stolen);
+    drop(own);
+    let handles: Vec<_> = std::iter::from_fn(|| first.try_get()).take(2).collect();
+    assert!(handles.iter().any(|handle| handle.entity() == entity));
+    assert!(handles
+        .iter()
+        .all(|handle| owned_by_first.contains(&handle.entity())));
+}
+
+/// Entities the shard of `pool` hands out first, returned to it afterwards.
+fn pool_entities(pool: &SharedEntityPool, count: usize) -> Vec<Entity> {
+    let handles: Vec<_> = (0..count).map(|_| pool.get()).collect();
+    handles.iter().map(SharedEntityHandle::entity).collect()
+}
+
+/// Waits for another thread to release an entity if all are in use.
+fn acquire(pool: &SharedEntityPool) -> SharedEntityHandle {
+    loop {
+        match pool.try_get() {
+            Some(handle) => return handle,
+            None => thread::yield_now(),
+        }
+    }
+}
+
+#[test]
+fn concurrent_jobs_finish_once_with_exclusive_entities() {
+    const THREADS: usize = 4;
+    const JOBS: usize = 500;
+
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 8);
+    let shared = pool.share(6, THREADS);
+    let held = Mutex::new(HashSet::new());
+    let finished: Vec<AtomicUsize> = (0..THREADS * JOBS).map(|_| AtomicUsize::new(0)).collect();
+
+    thread::scope(|scope| {
+        for thread in 0..THREADS {
+            let shared = shared.with_shard(thread);
+            let (held, finished) = (&held, &finished);
+            scope.spawn(move || {
+                for job in 0..JOBS {
+                    // shards hold one or two entities, so jobs holding two keep stealing
+                    let handles = [acquire(&shared), acquire(&shared)];
+                    for handle in &handles {
+                        assert!(held.lock().unwrap().insert(handle.entity()));
+                    }
+                    for handle in &handles {
+                        held.lock().unwrap().remove(&handle.entity());
+                    }
+                    finished[thread * JOBS + job].fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    assert!(finished
+        .iter()
+        .all(|count| count.load(Ordering::Relaxed) == 1));
+    assert_eq!(shared.available(), shared.capacity());
+
+    assert_eq!(pool.stats().in_use, 6);
+    drop(shared);
+    pool.reclaim(&mut world);
+    assert_eq!(pool.stats().in_use, 0);
+}
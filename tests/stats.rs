@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use bevy_entity_pool::EntityPool;
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn first_sample_seeds_the_averages() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 4);
+    let _handles: Vec<_> = (0..2).map(|_| pool.get()).collect();
+
+    pool.sample_stats();
+    let stats = pool.stats();
+    assert_eq!(stats.smoothed_occupancy, 0.5);
+    assert_eq!(stats.smoothed_acquire_rate, 2.0);
+}
+
+#[test]
+fn later_samples_decay_towards_current_usage() {
+    let mut world = World::new();
+    let mut pool = pool(&mut world, 4);
+    pool.set_stats_smoothing(0.5);
+    pool.sample_stats();
+
+    let _handles: Vec<_> = (0..4).map(|_| pool.get()).collect();
+    pool.sample_stats();
+    let stats = pool.stats();
+    assert_eq!(stats.smoothed_occupancy, 0.5);
+    assert_eq!(stats.smoothed_acquire_rate, 2.0);
+
+    pool.sample_stats();
+    let stats = pool.stats();
+    assert_eq!(stats.smoothed_occupancy, 0.75);
+    assert_eq!(stats.smoothed_acquire_rate, 1.0);
+    assert_eq!((stats.in_use, stats.total_acquisitions), (4, 4));
+}
+
+#[test]
+#[should_panic(expected = "stats smoothing factor must be in (0, 1]")]
+fn zero_smoothing_panics() {
+    let mut world = World::new();
+    pool(&mut world, 1).set_stats_smoothing(0.0);
+}
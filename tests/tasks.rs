@@ -0,0 +1,122 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, EntityPool, PooledTaskFinished, PooledTaskId, PooledTaskPlugin, PooledTasks,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Height(u32);
+
+fn app(capacity: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ))
+    .register_type::<Height>();
+    let entities = app.world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app.update();
+    app
+}
+
+/// Updates until task `id` was merged, returning its [`PooledTaskFinished`] event.
+fn finish(app: &mut App, id: PooledTaskId) -> PooledTaskFinished {
+    for _ in 0..1000 {
+        app.update();
+        let events = app.world.resource::<Events<PooledTaskFinished>>();
+        let finished = events
+            .get_reader()
+            .read(events)
+            .find(|event| event.id == id)
+            .cloned();
+        if let Some(finished) = finished {
+            return finished;
+        }
+        std::thread::yield_now();
+    }
+    panic!("task didn't finish");
+}
+
+#[test]
+fn results_are_merged_onto_the_checked_out_entities() {
+    let mut app = app(4);
+    let id = spawn_pooled_task(&mut app.world, 3, |context| {
+        for (height, entity) in context.entities().to_vec().into_iter().enumerate() {
+            context
+                .world
+                .entity_mut(entity)
+                .insert(Height(height as u32));
+        }
+    });
+    assert_eq!(app.world.resource::<EntityPool>().stats().in_use, 3);
+
+    let finished = finish(&mut app, id);
+    assert_eq!(finished.entities.len(), 3);
+    for (height, &entity) in finished.entities.iter().enumerate() {
+        assert_eq!(
+            app.world.get::<Height>(entity),
+            Some(&Height(height as u32))
+        );
+    }
+    assert!(!app.world.resource::<PooledTasks>().is_running(id));
+
+    let handles = app
+        .world
+        .resource_mut::<PooledTasks>()
+        .take_handles(id)
+        .unwrap();
+    let entities: Vec<Entity> = handles.iter().map(|handle| handle.entity()).collect();
+    assert_eq!(entities, finished.entities);
+    drop(handles);
+    app.update();
+    assert_eq!(app.world.resource::<EntityPool>().stats().in_use, 0);
+    assert!(entities
+        .iter()
+        .all(|&entity| app.world.get::<Height>(entity).is_none()));
+}
+
+#[test]
+fn cancelled_tasks_return_their_entities() {
+    let mut app = app(2);
+    let release = Arc::new(AtomicBool::new(false));
+    let id = {
+        let release = release.clone();
+        spawn_pooled_task(&mut app.world, 2, move |context| {
+            while !release.load(Ordering::Acquire) {
+                std::thread::yield_now();
+            }
+            for entity in context.entities().to_vec() {
+                context.world.entity_mut(entity).insert(Height(1));
+            }
+        })
+    };
+
+    assert!(app.world.resource_mut::<PooledTasks>().cancel(id));
+    release.store(true, Ordering::Release);
+    app.update();
+    let pool = app.world.resource::<EntityPool>();
+    assert_eq!(pool.stats().in_use, 0);
+    let entities = pool.entities().to_vec();
+    for _ in 0..10 {
+        app.update();
+    }
+    assert!(entities
+        .iter()
+        .all(|&entity| app.world.get::<Height>(entity).is_none()));
+    assert!(app
+        .world
+        .resource_mut::<PooledTasks>()
+        .take_handles(id)
+        .is_none());
+}
@@ -0,0 +1,90 @@
+use bevy::{prelude::*, scene::DynamicSceneBuilder};
+use bevy_entity_pool::{EntityPool, SlotTemplate};
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Health(u32);
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Tag;
+
+#[derive(Component)]
+struct Scratch;
+
+fn world() -> World {
+    let mut world = World::new();
+    let type_registry = AppTypeRegistry::default();
+    {
+        let mut registry = type_registry.write();
+        registry.register::<Health>();
+        registry.register::<Tag>();
+    }
+    world.insert_resource(type_registry);
+    world
+}
+
+/// Template alternating between a `Health(10)` and a `(Health(20), Tag)` layout.
+fn template(world: &World) -> SlotTemplate {
+    let mut source = World::new();
+    source.insert_resource(world.resource::<AppTypeRegistry>().clone());
+    source.spawn(Health(10));
+    source.spawn((Health(20), Tag));
+    let scene = DynamicSceneBuilder::from_world(&source)
+        .extract_entities(source.iter_entities().map(|entity| entity.id()))
+        .build();
+    SlotTemplate::from_scene(&scene)
+}
+
+fn pool(world: &mut World, capacity: u32) -> EntityPool {
+    let entities = world.entities().reserve_entities(capacity).collect();
+    EntityPool::new(entities, world)
+}
+
+#[test]
+fn slots_alternate_template_layouts() {
+    let mut world = world();
+    let mut pool = pool(&mut world, 4);
+    let template = template(&world);
+    assert_eq!(template.len(), 2);
+    pool.set_template(template, &mut world);
+
+    for (slot, &entity) in pool.entities().iter().enumerate() {
+        let entity = world.entity(entity);
+        let (health, tagged) = if slot % 2 == 0 {
+            (10, false)
+        } else {
+            (20, true)
+        };
+        assert_eq!(entity.get::<Health>(), Some(&Health(health)));
+        assert_eq!(entity.contains::<Tag>(), tagged);
+    }
+
+    let more = world.entities().reserve_entities(1).collect();
+    pool.grow(more, &mut world);
+    assert_eq!(world.get::<Health>(pool.entity(4)), Some(&Health(10)));
+}
+
+#[test]
+fn released_slots_are_reset_to_the_template() {
+    let mut world = world();
+    let mut pool = pool(&mut world, 1);
+    let held = pool.get();
+    pool.set_template(template(&world), &mut world);
+    assert!(world.get::<Health>(held.entity()).is_none());
+
+    world.entity_mut(held.entity()).insert((Health(3), Scratch));
+    let entity = held.entity();
+    drop(held);
+    pool.reclaim(&mut world);
+    assert_eq!(world.get::<Health>(entity), Some(&Health(10)));
+    assert!(world.get::<Scratch>(entity).is_none());
+
+    let template = pool.clear_template();
+    assert!(template.is_some() && pool.template().is_none());
+    let handle = pool.get();
+    world.entity_mut(entity).insert(Scratch);
+    drop(handle);
+    pool.reclaim(&mut world);
+    assert_eq!(world.entity(entity).archetype().components().count(), 0);
+}
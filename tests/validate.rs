@@ -0,0 +1,95 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    prelude::*,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, EntityPool, PooledTaskFinished, PooledTaskPlugin, PooledTaskRejected,
+    PooledTasks,
+};
+
+#[derive(Component, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+struct Height(f32);
+
+fn app(capacity: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+    ))
+    .register_type::<Height>();
+    let entities = app.world.entities().reserve_entities(capacity).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app.world
+        .resource_mut::<PooledTasks>()
+        .validators_mut()
+        .add_component::<Height>("finite height", |height| {
+            if height.0.is_finite() {
+                Ok(())
+            } else {
+                Err(format!("height is {}", height.0))
+            }
+        });
+    app.update();
+    app
+}
+
+fn run(app: &mut App, heights: [f32; 2]) -> Vec<Entity> {
+    let id = spawn_pooled_task(&mut app.world, 2, move |context| {
+        for (entity, height) in context.entities().to_vec().into_iter().zip(heights) {
+            context.world.entity_mut(entity).insert(Height(height));
+        }
+    });
+    for _ in 0..1000 {
+        app.update();
+        if !app.world.resource::<PooledTasks>().is_running(id) {
+            break;
+        }
+        std::thread::yield_now();
+    }
+    assert!(!app.world.resource::<PooledTasks>().is_running(id));
+
+    let handles = app
+        .world
+        .resource_mut::<PooledTasks>()
+        .take_handles(id)
+        .unwrap();
+    let entities = handles.iter().map(|handle| handle.entity()).collect();
+    drop(handles);
+    entities
+}
+
+#[test]
+fn rejected_results_are_not_merged() {
+    let mut app = app(2);
+    let entities = run(&mut app, [1.0, f32::NAN]);
+
+    let events = app.world.resource::<Events<PooledTaskRejected>>();
+    let rejected: Vec<_> = events.get_reader().read(events).cloned().collect();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].report.failures.len(), 1);
+    assert_eq!(rejected[0].report.failures[0].entity, entities[1]);
+    assert_eq!(rejected[0].report.failures[0].validator, "finite height");
+    let events = app.world.resource::<Events<PooledTaskFinished>>();
+    assert!(events.is_empty());
+
+    // neither the valid nor the invalid slot was copied back
+    assert!(entities
+        .iter()
+        .all(|&entity| app.world.get::<Height>(entity).is_none()));
+    app.update();
+    assert_eq!(app.world.resource::<EntityPool>().stats().in_use, 0);
+}
+
+#[test]
+fn valid_results_are_merged() {
+    let mut app = app(2);
+    let entities = run(&mut app, [1.0, 2.0]);
+
+    let events = app.world.resource::<Events<PooledTaskRejected>>();
+    assert!(events.is_empty());
+    assert_eq!(app.world.get::<Height>(entities[1]), Some(&Height(2.0)));
+}
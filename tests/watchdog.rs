@@ -0,0 +1,83 @@
+use bevy::{
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    ecs::event::ManualEventReader,
+    prelude::*,
+    utils::Duration,
+};
+use bevy_entity_pool::{
+    spawn_pooled_task, EntityPool, PooledTaskPlugin, PooledTaskStalled, PooledTaskWatchdog,
+    PooledTaskWatchdogPlugin, PooledTasks,
+};
+use std::thread;
+
+fn app(cancel_stalled: bool) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        TypeRegistrationPlugin,
+        FrameCountPlugin,
+        PooledTaskPlugin,
+        PooledTaskWatchdogPlugin {
+            settings: PooledTaskWatchdog {
+                window: Duration::from_millis(20),
+                cancel_stalled,
+            },
+        },
+    ));
+    let entities = app.world.entities().reserve_entities(2).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app.update();
+    app
+}
+
+/// Updates for a while past the watchdog window, collecting stall events.
+fn run_past_deadline(app: &mut App) -> Vec<PooledTaskStalled> {
+    let mut reader = ManualEventReader::<PooledTaskStalled>::default();
+    let mut stalled = Vec::new();
+    for _ in 0..10 {
+        thread::sleep(Duration::from_millis(10));
+        app.update();
+        let events = app.world.resource::<Events<PooledTaskStalled>>();
+        stalled.extend(reader.read(events).cloned());
+    }
+    stalled
+}
+
+#[test]
+fn stalled_tasks_are_cancelled() {
+    let mut app = app(true);
+    // never finishes on its own, only bails out once cancelled
+    let id = spawn_pooled_task(&mut app.world, 2, |context| {
+        while !context.progress.is_cancelled() {
+            thread::yield_now();
+        }
+    });
+    assert_eq!(app.world.resource::<EntityPool>().stats().in_use, 2);
+
+    let stalled = run_past_deadline(&mut app);
+    assert_eq!(stalled.len(), 1);
+    assert_eq!(stalled[0].id, id);
+    assert!(stalled[0].cancelled);
+    assert!(stalled[0].stalled_for >= Duration::from_millis(20));
+
+    assert!(!app.world.resource::<PooledTasks>().is_running(id));
+    assert_eq!(app.world.resource::<EntityPool>().stats().in_use, 0);
+}
+
+#[test]
+fn stalled_tasks_are_flagged_once_without_cancelling() {
+    let mut app = app(false);
+    let id = spawn_pooled_task(&mut app.world, 1, |context| {
+        while !context.progress.is_cancelled() {
+            thread::yield_now();
+        }
+    });
+
+    let stalled = run_past_deadline(&mut app);
+    assert_eq!(stalled.len(), 1);
+    assert!(!stalled[0].cancelled);
+    assert!(app.world.resource::<PooledTasks>().is_running(id));
+
+    app.world.resource_mut::<PooledTasks>().cancel(id);
+}
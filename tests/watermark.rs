@@ -0,0 +1,74 @@
+use bevy::{ecs::event::ManualEventReader, prelude::*};
+use bevy_entity_pool::{EntityPool, PoolWatermarkPlugin, WatermarkCrossed};
+use std::sync::{Arc, Mutex};
+
+fn app(thresholds: Vec<f32>) -> App {
+    let mut app = App::new();
+    app.add_plugins(PoolWatermarkPlugin { thresholds });
+    let entities = app.world.entities().reserve_entities(4).collect();
+    let pool = EntityPool::new(entities, &mut app.world);
+    app.world.insert_resource(pool);
+    app
+}
+
+#[test]
+fn crossings_are_sent_once_per_direction() {
+    let mut app = app(vec![0.5, 1.0]);
+    let mut reader = ManualEventReader::<WatermarkCrossed>::default();
+    let crossed = |app: &App, reader: &mut ManualEventReader<WatermarkCrossed>| {
+        reader
+            .read(app.world.resource::<Events<WatermarkCrossed>>())
+            .map(|event| (event.threshold, event.rising))
+            .collect::<Vec<_>>()
+    };
+
+    let handles: Vec<_> = {
+        let mut pool = app.world.resource_mut::<EntityPool>();
+        (0..2).map(|_| pool.get()).collect()
+    };
+    app.update();
+    assert_eq!(crossed(&app, &mut reader), [(0.5, true)]);
+    app.update();
+    assert!(crossed(&app, &mut reader).is_empty());
+
+    drop(handles);
+    app.world
+        .resource_scope(|world, mut pool: Mut<EntityPool>| pool.reclaim(world));
+    app.update();
+    assert_eq!(crossed(&app, &mut reader), [(0.5, false)]);
+}
+
+#[test]
+fn callbacks_see_crossings_within_a_frame() {
+    let mut pool = app(Vec::new())
+        .world
+        .remove_resource::<EntityPool>()
+        .unwrap();
+    let crossings = Arc::new(Mutex::new(Vec::new()));
+    let seen = crossings.clone();
+    pool.on_watermark(0.25, move |crossed| {
+        seen.lock().unwrap().push(crossed.rising)
+    });
+
+    let handle = pool.get();
+    drop(handle);
+    assert_eq!(*crossings.lock().unwrap(), [true]);
+}
+
+#[test]
+#[should_panic(expected = "must be in 0..=1")]
+fn plugin_rejects_thresholds_above_capacity() {
+    app(vec![0.5, 1.5]);
+}
+
+#[test]
+#[should_panic(expected = "strictly ascending")]
+fn plugin_rejects_unordered_thresholds() {
+    app(vec![0.9, 0.75]);
+}
+
+#[test]
+#[should_panic(expected = "strictly ascending")]
+fn plugin_rejects_duplicate_thresholds() {
+    app(vec![0.9, 0.9]);
+}